@@ -0,0 +1,628 @@
+//
+// Copyright 2020 bplist Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+//! Random access to the objects within a bplist document.
+//!
+//! Where the deserializer decodes a document into a single Rust value, an archive
+//! retains the parsed document metadata and allows the object graph to be inspected
+//! directly. This is useful for tooling which needs to understand the structure of a
+//! document, for example the references between objects in a keyed archive.
+
+use serde::{Deserialize, Serialize, Serializer};
+use serde::de::DeserializeOwned;
+use serde::ser::{SerializeMap, SerializeSeq};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use std::ops::Range;
+use std::slice;
+
+use crate::de::{Deserializer, ObjectDeserializer, ObjectTable};
+use crate::document::{DocumentReport, ObjectFormat, Warning};
+use crate::error::{Error, Result};
+use crate::object::{Object, ObjectRef, Uid};
+use crate::pointer;
+use crate::ser::{self, EncodeOptions};
+
+/// The metadata of a document produced by `NSKeyedArchiver`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct KeyedArchiveInfo {
+    /// The name of the archiver, typically `NSKeyedArchiver`.
+    pub archiver: String,
+    /// The version of the archive format.
+    pub version: u64,
+    /// The UIDs of the top-level objects in `$objects`, keyed by name.
+    pub top: BTreeMap<String, Uid>,
+}
+
+/// A parsed bplist document providing access to the objects it contains.
+#[derive(Debug)]
+pub struct Archive<'a> {
+    /// The object table of the document.
+    object_table: ObjectTable<'a>,
+}
+
+impl<'a> Archive<'a> {
+
+    /// Parses the metadata of the bplist document in `input`.
+    ///
+    /// # Errors
+    ///
+    /// This can fail if the header, trailer or offset table of the document is
+    /// missing or invalid. Objects are not decoded until they are accessed.
+    pub fn from_slice(input: &'a [u8]) -> Result<Self> {
+        Ok(Archive::from_object_table(Deserializer::from_slice(input).take_object_table()?))
+    }
+
+    /// Creates an archive for an object table which has already been parsed.
+    pub(super) fn from_object_table(object_table: ObjectTable<'a>) -> Self {
+        Archive {
+            object_table,
+        }
+    }
+
+    /// Returns the offset of each object from the start of the document, indexed by object.
+    ///
+    /// The offsets are returned as they appear in the offset table. They are neither
+    /// required to be in ascending order nor to be within the object table.
+    pub fn object_offsets(&self) -> &[usize] {
+        &self.object_table.metadata.offset_table
+    }
+
+    /// Returns the range of bytes of the document in which objects may reside.
+    ///
+    /// This extends from the end of the header to the start of the offset table.
+    pub fn object_table_range(&self) -> Range<usize> {
+        self.object_table.metadata.object_table_range.clone()
+    }
+
+    /// Returns the sort version recorded in the trailer of the document.
+    ///
+    /// This is 0 for documents written by CoreFoundation. Any other value indicates
+    /// that dictionary keys were ordered by an unknown algorithm, which re-encoding
+    /// the document does not preserve.
+    pub fn sort_version(&self) -> u8 {
+        self.object_table.metadata.sort_version
+    }
+
+    /// Returns the value of every UID object reachable from the root object.
+    ///
+    /// The values are returned in depth-first order of the object graph, with the
+    /// keys and values of dictionaries visited pairwise. Results are per object rather
+    /// than per reference: a UID object referenced from several collections is returned
+    /// once, while equal values stored in distinct objects are each returned.
+    pub fn uids(&self) -> Result<Vec<Uid>> {
+        let mut uids = Vec::new();
+        self.walk(|object, format| {
+            if format == ObjectFormat::Uid {
+                uids.push(Uid(self.object_table.parse_uid(object)?));
+            }
+            Ok(())
+        })?;
+        Ok(uids)
+    }
+
+    /// Scans every array and dictionary in the object table for references to objects
+    /// which are not in the offset table.
+    ///
+    /// Unlike deserialization, which stops at the first invalid reference, this returns
+    /// every dangling reference in the document as a pair of the index of the collection
+    /// and the invalid reference, ordered by collection. Collections which are not
+    /// reachable from the root object are scanned as well. Objects which cannot be parsed
+    /// are skipped, so that the rest of the document is still scanned.
+    pub fn validate_references(&self) -> Vec<(usize, usize)> {
+        let number_of_objects = self.object_table.metadata.offset_table.len();
+        let mut dangling_references = Vec::new();
+
+        for object in 0 .. number_of_objects {
+            let references = match self.object_table.kind_of(object) {
+                Ok(ObjectFormat::Array) =>
+                    self.object_table.parse_array(object),
+                Ok(ObjectFormat::Dictionary) =>
+                    self.object_table.parse_dictionary(object).map(|pairs| {
+                        pairs.into_iter().flat_map(|(key, value)| vec![key, value]).collect()
+                    }),
+                _ =>
+                    continue,
+            };
+
+            if let Ok(references) = references {
+                dangling_references.extend(
+                    references.into_iter()
+                        .filter(|&reference| reference >= number_of_objects)
+                        .map(|reference| (object, reference))
+                );
+            }
+        }
+
+        dangling_references
+    }
+
+    /// Reports the objects in the object table which are not reachable from the root object.
+    pub(crate) fn report(&self) -> Result<DocumentReport> {
+        let number_of_objects = self.object_table.metadata.offset_table.len();
+        let mut reachable = vec![false; number_of_objects];
+        self.walk(|object, _| {
+            reachable[object] = true;
+            Ok(())
+        })?;
+
+        Ok(DocumentReport {
+            number_of_objects,
+            unreachable_objects: unreachable_objects(reachable),
+        })
+    }
+
+    /// Reports the non-fatal anomalies in the document.
+    ///
+    /// Oversized object references are reported first, followed by non-minimal integers
+    /// in the order they are reachable from the root object, and unreachable objects.
+    pub(crate) fn warnings(&self) -> Result<Vec<Warning>> {
+        let mut warnings = Vec::new();
+
+        let size = self.object_table.metadata.object_reference_size;
+        let number_of_objects = self.object_table.metadata.offset_table.len();
+        let minimum_size = ser::size_of_value(number_of_objects.saturating_sub(1));
+        if size > minimum_size {
+            warnings.push(Warning::OversizedObjectReferences { size, minimum_size });
+        }
+
+        // Integers are minimal if they could not be encoded in a narrower format.
+        let mut reachable = vec![false; number_of_objects];
+        self.walk(|object, format| {
+            reachable[object] = true;
+            let is_minimal = match format {
+                ObjectFormat::UInt16 =>
+                    self.object_table.parse_uint16(object)? > u16::from(u8::MAX),
+                ObjectFormat::UInt32 =>
+                    self.object_table.parse_uint32(object)? > u32::from(u16::MAX),
+                ObjectFormat::SInt64 => {
+                    let value = self.object_table.parse_sint64(object)?;
+                    value < 0 || value > i64::from(u32::MAX)
+                }
+                ObjectFormat::SInt128 =>
+                    i64::try_from(self.object_table.parse_sint128(object)?).is_err(),
+                _ =>
+                    true,
+            };
+            if !is_minimal {
+                warnings.push(Warning::NonMinimalInteger { object });
+            }
+            Ok(())
+        })?;
+
+        warnings.extend(
+            unreachable_objects(reachable).into_iter()
+                .map(|object| Warning::UnreachableObject { object })
+        );
+        Ok(warnings)
+    }
+
+    /// Decodes the elements of the root array, borrowing strings and data from the input.
+    ///
+    /// This avoids copying the contents of ASCII strings and data objects, which makes
+    /// it suitable for scanning large arrays.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ExpectedArray` if the root object is not an array.
+    pub fn root_array_ref(&self) -> Result<Vec<ObjectRef<'a>>> {
+        let root_object = self.object_table.metadata.root_object;
+        if self.object_table.kind_of(root_object)? != ObjectFormat::Array {
+            return Err(Error::ExpectedArray);
+        }
+
+        let mut deserializer = ObjectDeserializer::new(&self.object_table, root_object);
+        Vec::<ObjectRef<'a>>::deserialize(&mut deserializer)
+    }
+
+    /// Decodes the entries of the specified dictionary object in the order in which
+    /// they appear in the document.
+    ///
+    /// Decoding a dictionary into a map loses the order of its entries, which is
+    /// preserved here for tools which need to reproduce it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ExpectedDictionary` if the object is not a dictionary, or any
+    /// error encountered decoding the keys and values as `K` and `V`.
+    pub fn ordered_entries<K, V>(&self, index: usize) -> Result<Vec<(K, V)>>
+    where
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        if self.object_table.kind_of(index)? != ObjectFormat::Dictionary {
+            return Err(Error::ExpectedDictionary);
+        }
+
+        // A single deserializer is reused for every entry to avoid reallocating its state.
+        let pairs = self.object_table.parse_dictionary(index)?;
+        let mut deserializer = ObjectDeserializer::new(&self.object_table, index);
+        deserializer.enter_collection(index)?;
+        pairs.into_iter()
+            .map(|(key, value)| {
+                deserializer.set_next_object(key);
+                let key = K::deserialize(&mut deserializer)?;
+                deserializer.set_next_object(value);
+                let value = V::deserialize(&mut deserializer)?;
+                Ok((key, value))
+            })
+            .collect()
+    }
+
+    /// Returns the contents of the specified data object in chunks of `chunk_size`
+    /// bytes, borrowed from the input.
+    ///
+    /// This allows large binary payloads to be passed to a consumer incrementally
+    /// without copying them. The last chunk is shorter if the length of the data is
+    /// not a multiple of `chunk_size`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ExpectedData` if the object is not a data object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is 0.
+    pub fn data_chunks(&self, index: usize, chunk_size: usize) -> Result<slice::Chunks<'a, u8>> {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        Ok(self.object_table.parse_data(index)?.chunks(chunk_size))
+    }
+
+    /// Decodes the document and encodes it again with object references of exactly
+    /// `size` bytes.
+    ///
+    /// This is intended for generating documents which exercise decoders of specific
+    /// reference widths. The document is re-encoded from its decoded objects, so the
+    /// order of dictionary entries and the layout of the object table may differ.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the document cannot be decoded, or if `size` is not between 1 and 8
+    /// bytes or is too small to reference every object in the re-encoded document.
+    pub fn reencode_with_reference_size(&self, size: usize) -> Result<Vec<u8>> {
+        let root_object = self.deserialize_object::<Object>(self.object_table.metadata.root_object)?;
+        EncodeOptions::new().object_reference_size(size).to_bytes(&root_object)
+    }
+
+    /// Decodes the document, applies `edit` to the root object and encodes the result.
+    ///
+    /// This is intended for tools which modify part of a document and want the output
+    /// to differ from the input as little as possible. Dictionary entries are written
+    /// in the order in which they appear in the input, followed by any entries added by
+    /// the edit in sorted order. Collections are matched with their counterparts in
+    /// the input by key or index. The object table is otherwise laid out as
+    /// `bplist::to_bytes` lays it out, so the output is byte-identical to the input only
+    /// if the input was written in the same way, as CoreFoundation writes documents.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the document cannot be decoded, if `edit` fails, or if the edited root
+    /// object cannot be encoded.
+    pub fn reserialize_with_edit<F>(&self, edit: F) -> Result<Vec<u8>>
+    where
+        F: FnOnce(&mut Object) -> Result<()>,
+    {
+        // Decoding the root object first rejects documents with reference cycles.
+        let root_object = self.object_table.metadata.root_object;
+        let mut object = self.deserialize_object::<Object>(root_object)?;
+        let mut deserializer = ObjectDeserializer::new(&self.object_table, root_object);
+        let layout = self.layout(root_object, &mut deserializer)?;
+        edit(&mut object)?;
+        EncodeOptions::new()
+            .preserve_entry_order(true)
+            .to_bytes(&LayoutObject { object: &object, layout: Some(&layout) })
+    }
+
+    /// Navigates to the object identified by a JSON Pointer and deserializes it as `T`.
+    ///
+    /// Each reference token of the pointer selects either the value for a string key
+    /// of a dictionary, or an element of an array by index. Only the object found, and
+    /// the objects it contains, are decoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Message` if the pointer is malformed or there is no object at the
+    /// location it identifies. Deserialization of the object can fail as described in
+    /// `bplist::from_slice`.
+    pub fn get_at<T>(&self, pointer: &str) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let object = self.resolve(pointer)?.ok_or_else(|| {
+            Error::Message(format!("no object found at `{}`", pointer))
+        })?;
+        self.deserialize_object(object)
+    }
+
+    /// Decodes the value for a string key of the root dictionary as `T`.
+    ///
+    /// Only the value for the key is decoded. The other values of the dictionary are
+    /// not visited, which makes this suitable for extracting a single setting from a
+    /// large document. Returns `None` if the dictionary has no such key.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ExpectedDictionary` if the root object is not a dictionary.
+    /// Deserialization of the value can fail as described in `bplist::from_slice`.
+    pub fn decode_lazy_field<T>(&self, key: &str) -> Result<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let root_object = self.object_table.metadata.root_object;
+        if self.object_table.kind_of(root_object)? != ObjectFormat::Dictionary {
+            return Err(Error::ExpectedDictionary);
+        }
+
+        self.find_value(root_object, key)?
+            .map(|value| self.deserialize_object(value))
+            .transpose()
+    }
+
+    /// Returns whether the document is a keyed archive, such as one produced by
+    /// `NSKeyedArchiver`.
+    ///
+    /// A document is treated as a keyed archive if the root object is a dictionary with
+    /// `$archiver`, `$objects`, `$top` and `$version` keys. Only the keys of the root
+    /// dictionary are decoded.
+    pub fn is_keyed_archive(&self) -> Result<bool> {
+        let root_object = self.object_table.metadata.root_object;
+        if self.object_table.kind_of(root_object)? != ObjectFormat::Dictionary {
+            return Ok(false);
+        }
+
+        for key in &["$archiver", "$objects", "$top", "$version"] {
+            if self.find_value(root_object, key)?.is_none() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Returns the `$archiver`, `$version` and `$top` metadata of a keyed archive.
+    ///
+    /// A document is treated as a keyed archive if the root object is a dictionary with
+    /// `$archiver`, `$version`, `$top` and `$objects` keys. Returns `None` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the document is a keyed archive but the metadata values are not of the
+    /// expected types.
+    pub fn keyed_archive_info(&self) -> Result<Option<KeyedArchiveInfo>> {
+        let root_object = self.object_table.metadata.root_object;
+        if self.object_table.kind_of(root_object)? != ObjectFormat::Dictionary {
+            return Ok(None);
+        }
+
+        let archiver = self.find_value(root_object, "$archiver")?;
+        let version = self.find_value(root_object, "$version")?;
+        let top = self.find_value(root_object, "$top")?;
+        let objects = self.find_value(root_object, "$objects")?;
+        match (archiver, version, top, objects) {
+            (Some(archiver), Some(version), Some(top), Some(_)) =>
+                Ok(Some(KeyedArchiveInfo {
+                    archiver: self.deserialize_object(archiver)?,
+                    version: self.deserialize_object(version)?,
+                    top: self.deserialize_object(top)?,
+                })),
+            _ =>
+                Ok(None),
+        }
+    }
+
+    /// Deserializes the specified object as `T`.
+    fn deserialize_object<T>(&self, object: usize) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let mut deserializer = ObjectDeserializer::new(&self.object_table, object);
+        T::deserialize(&mut deserializer)
+    }
+
+    /// Returns the index of the object identified by a JSON Pointer, if it exists.
+    fn resolve(&self, pointer: &str) -> Result<Option<usize>> {
+        let tokens = pointer::tokens(pointer).ok_or_else(|| {
+            Error::Message(format!("invalid pointer `{}`", pointer))
+        })?;
+
+        let mut object = self.object_table.metadata.root_object;
+        for token in tokens {
+            let next_object = match self.object_table.kind_of(object)? {
+                ObjectFormat::Array => {
+                    let objects = self.object_table.parse_array(object)?;
+                    pointer::index(&token).and_then(|index| objects.get(index).cloned())
+                }
+                ObjectFormat::Dictionary =>
+                    self.find_value(object, &token)?,
+                _ =>
+                    None,
+            };
+
+            match next_object {
+                Some(next_object) => object = next_object,
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(object))
+    }
+
+    /// Returns the value for the string key in the dictionary, if present.
+    fn find_value(&self, dictionary: usize, key: &str) -> Result<Option<usize>> {
+        for (key_object, value_object) in self.object_table.parse_dictionary(dictionary)? {
+            let is_match = match self.object_table.kind_of(key_object)? {
+                ObjectFormat::AsciiString =>
+                    self.object_table.parse_ascii_text(key_object)? == key,
+                ObjectFormat::Utf16String =>
+                    self.object_table.parse_utf16_string(key_object)? == key,
+                _ =>
+                    false,
+            };
+            if is_match {
+                return Ok(Some(value_object));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the order of the entries of the specified object and the objects it
+    /// contains, decoding dictionary keys with `deserializer`.
+    fn layout(&self, object: usize, deserializer: &mut ObjectDeserializer) -> Result<Layout> {
+        match self.object_table.kind_of(object)? {
+            ObjectFormat::Array =>
+                self.object_table.parse_array(object)?
+                    .into_iter()
+                    .map(|element| self.layout(element, deserializer))
+                    .collect::<Result<_>>()
+                    .map(Layout::Array),
+            ObjectFormat::Dictionary =>
+                self.object_table.parse_dictionary(object)?
+                    .into_iter()
+                    .map(|(key, value)| {
+                        deserializer.set_next_object(key);
+                        let key = Object::deserialize(&mut *deserializer)?;
+                        Ok((key, self.layout(value, deserializer)?))
+                    })
+                    .collect::<Result<_>>()
+                    .map(Layout::Dictionary),
+            _ =>
+                Ok(Layout::Scalar),
+        }
+    }
+
+    /// Performs a depth-first traversal of the objects reachable from the root object,
+    /// invoking the callback once for every object along with its format.
+    fn walk<F>(&self, mut callback: F) -> Result<()>
+    where
+        F: FnMut(usize, ObjectFormat) -> Result<()>,
+    {
+        let mut visited = BTreeSet::new();
+        let mut pending = vec![self.object_table.metadata.root_object];
+
+        while let Some(object) = pending.pop() {
+            if !visited.insert(object) {
+                continue;
+            }
+
+            let format = self.object_table.kind_of(object)?;
+            callback(object, format)?;
+
+            // Children are pushed in reverse to be visited in the order they are referenced.
+            match format {
+                ObjectFormat::Array => {
+                    let objects = self.object_table.parse_array(object)?;
+                    pending.extend(objects.into_iter().rev());
+                }
+                ObjectFormat::Dictionary => {
+                    let pairs = self.object_table.parse_dictionary(object)?;
+                    for (key, value) in pairs.into_iter().rev() {
+                        pending.push(value);
+                        pending.push(key);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+}
+
+/// Returns the indices of the objects which are not marked as reachable.
+fn unreachable_objects(reachable: Vec<bool>) -> Vec<usize> {
+    reachable.into_iter()
+        .enumerate()
+        .filter(|&(_, is_reachable)| !is_reachable)
+        .map(|(object, _)| object)
+        .collect()
+}
+
+/// The order of the entries of an object in a document, and of the objects it contains.
+#[derive(Debug)]
+enum Layout {
+    /// An object which does not contain other objects.
+    Scalar,
+    /// An array with the layout of each of its elements.
+    Array(Vec<Layout>),
+    /// A dictionary with its keys and the layout of their values, in document order.
+    Dictionary(Vec<(Object, Layout)>),
+}
+
+/// An object which is serialized with the entry order of a layout, where it has one.
+struct LayoutObject<'a> {
+    /// The object to serialize.
+    object: &'a Object,
+    /// The layout of the counterpart of the object in the original document.
+    layout: Option<&'a Layout>,
+}
+
+impl<'a> Serialize for LayoutObject<'a> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match (self.object, self.layout) {
+            (Object::Array(objects), Some(Layout::Array(layouts))) => {
+                let mut sequence = serializer.serialize_seq(Some(objects.len()))?;
+                for (index, object) in objects.iter().enumerate() {
+                    let layout = layouts.get(index);
+                    sequence.serialize_element(&LayoutObject { object, layout })?;
+                }
+                sequence.end()
+            }
+            (Object::Dictionary(pairs), Some(Layout::Dictionary(entries))) => {
+                let mut map = serializer.serialize_map(Some(pairs.len()))?;
+                let mut written_keys = BTreeSet::new();
+                for (key, layout) in entries {
+                    if let Some(object) = pairs.get(key) {
+                        if written_keys.insert(key) {
+                            let layout = Some(layout);
+                            map.serialize_entry(key, &LayoutObject { object, layout })?;
+                        }
+                    }
+                }
+                for (key, object) in pairs {
+                    if !written_keys.contains(key) {
+                        map.serialize_entry(key, &LayoutObject { object, layout: None })?;
+                    }
+                }
+                map.end()
+            }
+            (object, _) =>
+                object.serialize(serializer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::Archive;
+    use crate::object::Object;
+
+    #[test]
+    fn test_formats_are_cached_across_accesses() {
+        let data = crate::to_bytes(&vec![vec![1u8, 2], vec![3]]).unwrap();
+        let archive = Archive::from_slice(&data).unwrap();
+        let cached_formats = || {
+            archive.object_table.formats.iter()
+                .filter(|format| format.load(Ordering::Relaxed) != 0)
+                .count()
+        };
+        assert_eq!(cached_formats(), 0);
+
+        // Decoding through the archive caches the format of every object it visits.
+        let expected = crate::from_slice::<Object>(&data).unwrap();
+        assert_eq!(archive.get_at::<Object>(""), Ok(expected.clone()));
+        assert_eq!(cached_formats(), archive.object_offsets().len());
+        assert_eq!(archive.get_at::<Object>(""), Ok(expected));
+    }
+}
@@ -16,7 +16,7 @@ use serde::de;
 
 use std::fmt;
 
-use crate::object::Date;
+use crate::object::{Date, SystemTimeFromDate};
 
 /// Name of the Date structure.
 pub const STRUCT_NAME: &str = "$__bplist_private_Date";
@@ -62,6 +62,19 @@ impl<'de> de::Deserialize<'de> for Date {
     }
 }
 
+/// Deserializes a Date and converts it into a `SystemTime`.
+impl<'de> de::Deserialize<'de> for SystemTimeFromDate {
+    fn deserialize<D>(deserializer: D) -> Result<SystemTimeFromDate, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let date = Date::deserialize(deserializer)?;
+        date.to_system_time()
+            .map(SystemTimeFromDate)
+            .ok_or_else(|| de::Error::custom("date is out of range for system time"))
+    }
+}
+
 struct DateKey;
 
 /// Deserializes the custom date struct field.
@@ -9,28 +9,45 @@
 
 pub(crate) mod date;
 pub(crate) mod uid;
+mod archive;
 mod parser;
 
+pub use archive::{Archive, KeyedArchiveInfo};
+
 use serde::Deserialize;
 use serde::de::{
     self,
+    DeserializeOwned,
     DeserializeSeed,
+    EnumAccess,
     IntoDeserializer,
     MapAccess,
     SeqAccess,
+    Unexpected,
+    VariantAccess,
 };
 
-use std::collections::BTreeSet;
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::io;
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::vec;
 
 use crate::document::{
     HEADER_SIZE,
     HEADER_VERSION_00,
     TRAILER_SIZE,
+    DecodeStats,
+    DocumentReport,
+    Header,
+    Inspection,
     OffsetTable,
     ObjectFormat,
+    Trailer,
+    Warning,
 };
 use crate::error::{Error, Result};
+use crate::object::Object;
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 struct Metadata {
@@ -42,6 +59,8 @@ struct Metadata {
     root_object: usize,
     /// The range of bytes of the input where objects may reside.
     object_table_range: std::ops::Range<usize>,
+    /// The sort version recorded in the trailer.
+    sort_version: u8,
 }
 
 impl Metadata {
@@ -55,13 +74,68 @@ impl Metadata {
     }
 }
 
+/// The default maximum number of collections which may be nested within each other.
+const DEFAULT_RECURSION_LIMIT: usize = 128;
+
+/// Options which control how the objects of a document are interpreted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Options {
+    /// Whether ASCII strings containing bytes above 0x7F are decoded as Latin-1.
+    lenient_ascii: bool,
+    /// The maximum number of collections which may be nested within each other.
+    recursion_limit: usize,
+    /// Whether documents with a nonzero sort version are rejected.
+    strict_sort_version: bool,
+    /// Whether integers 0 and 1 and booleans are interchangeable.
+    lenient_booleans: bool,
+    /// Whether types are asked for their human-readable representation.
+    human_readable: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            lenient_ascii: false,
+            recursion_limit: DEFAULT_RECURSION_LIMIT,
+            strict_sort_version: false,
+            lenient_booleans: false,
+            human_readable: true,
+        }
+    }
+}
+
 /// Provides access to objects within the object table.
 #[derive(Debug)]
 struct ObjectTable<'a> {
     input: &'a [u8],
     metadata: Metadata,
+    options: Options,
+    /// The format of each object in the offset table, once its marker has been parsed,
+    /// as one more than its index in `OBJECT_FORMATS`, or zero if not yet parsed.
+    formats: Vec<AtomicU8>,
 }
 
+/// Every object format, indexed by the formats cached in an object table.
+const OBJECT_FORMATS: [ObjectFormat; 17] = [
+    ObjectFormat::Null,
+    ObjectFormat::Boolean,
+    ObjectFormat::Fill,
+    ObjectFormat::UInt8,
+    ObjectFormat::UInt16,
+    ObjectFormat::UInt32,
+    ObjectFormat::SInt64,
+    ObjectFormat::SInt128,
+    ObjectFormat::Float32,
+    ObjectFormat::Float64,
+    ObjectFormat::Date,
+    ObjectFormat::Data,
+    ObjectFormat::AsciiString,
+    ObjectFormat::Utf16String,
+    ObjectFormat::Uid,
+    ObjectFormat::Array,
+    ObjectFormat::Dictionary,
+];
+
 /// Defines a basic parser with serde-compatible error handling.
 macro_rules! define_parser {
     ($name:ident, $parser:expr, $type:ty, $expected_error:path) => {
@@ -77,23 +151,42 @@ macro_rules! define_parser {
 impl<'a> ObjectTable<'a> {
 
     /// Returns the slice of the input corresponding to the object.
-    fn data_for(&self, object: usize) -> Result<&[u8]> {
+    fn data_for(&self, object: usize) -> Result<&'a [u8]> {
         let offset = self.metadata.offset_of(object)?;
 
         // Make sure the offset is to a point within the object table.
         if !self.metadata.object_table_range.contains(&offset) {
-            return Err(Error::InvalidOffsetToObject);
+            return Err(Error::At {
+                offset,
+                source: Box::new(Error::InvalidOffsetToObject),
+            });
         }
 
-        Ok(&self.input[offset .. ])
+        Ok(&self.input[offset .. self.metadata.object_table_range.end])
     }
 
     /// Parses the marker byte for the specified object and returns the format.
+    ///
+    /// The format is cached, so the marker of each object is parsed once, unless it is
+    /// first accessed from several threads at the same time.
     fn kind_of(&self, object: usize) -> Result<ObjectFormat> {
+        let cached = self.formats.get(object).map_or(0, |format| format.load(Ordering::Relaxed));
+        if cached != 0 {
+            return Ok(OBJECT_FORMATS[usize::from(cached - 1)]);
+        }
+
+        let offset = self.metadata.offset_of(object)?;
         let data = self.data_for(object)?;
-        parser::object::any_marker(data)
+        let format = parser::object::any_marker(data)
             .map(|(_, (format, _))| format)
-            .map_err(|_| Error::InvalidOrUnsupportedObjectFormat)
+            .map_err(|_| Error::At {
+                offset,
+                source: Box::new(Error::InvalidOrUnsupportedObjectFormat),
+            })?;
+        if let Some(index) = OBJECT_FORMATS.iter().position(|&known| known == format) {
+            self.formats[object].store(index as u8 + 1, Ordering::Relaxed);
+        }
+        Ok(format)
     }
 
     define_parser![
@@ -102,6 +195,12 @@ impl<'a> ObjectTable<'a> {
         bool,
         Error::ExpectedBool
     ];
+    define_parser![
+        parse_null,
+        parser::object::null,
+        (),
+        Error::ExpectedNull
+    ];
     define_parser![
         parse_fill,
         parser::object::fill,
@@ -132,6 +231,12 @@ impl<'a> ObjectTable<'a> {
         i64,
         Error::ExpectedSInt64
     ];
+    define_parser![
+        parse_sint128,
+        parser::object::sint128,
+        i128,
+        Error::ExpectedSInt128
+    ];
     define_parser![
         parse_float32,
         parser::object::float32,
@@ -153,20 +258,26 @@ impl<'a> ObjectTable<'a> {
     define_parser![
         parse_data, 
         parser::object::data,
-        &[u8],
+        &'a [u8],
         Error::ExpectedData
     ];
     define_parser![
         parse_ascii_string,
         parser::object::ascii_string,
-        &str,
+        &'a str,
+        Error::ExpectedAsciiString
+    ];
+    define_parser![
+        parse_latin1_string,
+        parser::object::latin1_string,
+        String,
         Error::ExpectedAsciiString
     ];
     define_parser![
         parse_utf16_string,
         parser::object::utf16_string,
         String,
-        Error::ExpectedAsciiString
+        Error::ExpectedUtf16String
     ];
     define_parser![
         parse_uid,
@@ -175,6 +286,19 @@ impl<'a> ObjectTable<'a> {
         Error::ExpectedUid
     ];
 
+    /// Parses an ASCII string, borrowing it from the input. If the string is not valid
+    /// ASCII and lenient ASCII decoding is enabled, it is instead decoded as Latin-1.
+    fn parse_ascii_text(&self, object: usize) -> Result<Cow<'a, str>> {
+        match self.parse_ascii_string(object) {
+            Ok(value) =>
+                Ok(Cow::Borrowed(value)),
+            Err(_) if self.options.lenient_ascii =>
+                self.parse_latin1_string(object).map(Cow::Owned),
+            Err(error) =>
+                Err(error),
+        }
+    }
+
     /// Parses an array of objects whose reference size is determined in metadata.
     fn parse_array(&self, object: usize) -> Result<Vec<usize>> {
         let data = self.data_for(object)?;
@@ -183,9 +307,20 @@ impl<'a> ObjectTable<'a> {
             .map_err(|_| Error::ExpectedArray)
     }
 
-    /// Parses an array of objects whose reference size is determined in metadata.
+    /// Parses a dictionary of objects whose reference size is determined in metadata.
     fn parse_dictionary(&self, object: usize) -> Result<Vec<(usize, usize)>> {
         let data = self.data_for(object)?;
+
+        // Every key reference must be matched by a value reference.
+        let (references, entry_count) = parser::object::dictionary_entry_count(data)
+            .map_err(|_| Error::ExpectedDictionary)?;
+        let references_length = entry_count
+            .checked_mul(2 * self.metadata.object_reference_size);
+        let fits = matches!(references_length, Some(length) if length <= references.len());
+        if !fits {
+            return Err(Error::TruncatedDictionary);
+        }
+
         parser::object::dictionary(self.metadata.object_reference_size)(data)
             .map(|(_, pairs)| pairs)
             .map_err(|_| Error::ExpectedDictionary)
@@ -198,12 +333,87 @@ impl<'a> ObjectTable<'a> {
 pub struct Deserializer<'de> {
     /// The bytes which represent the totality of the input document.
     input: &'de [u8],
+    /// Options which control how objects are interpreted.
+    options: Options,
+    /// Statistics gathered by the most recent deserialization.
+    stats: DecodeStats,
+    /// The object table, once parsed, which is reused while the options are unchanged.
+    object_table: Option<ObjectTable<'de>>,
 }
 
 impl<'de> Deserializer<'de> {
     /// Creates a bplist deserializer from a `&[u8]`.
     pub fn from_slice(input: &'de [u8]) -> Self {
-        Deserializer { input }
+        Deserializer {
+            input,
+            options: Options::default(),
+            stats: DecodeStats::default(),
+            object_table: None,
+        }
+    }
+
+    /// Returns the statistics gathered by the most recent deserialization.
+    pub fn stats(&self) -> DecodeStats {
+        self.stats
+    }
+
+    /// Sets whether ASCII strings containing bytes above 0x7F are accepted.
+    ///
+    /// Some encoders mislabel Latin-1 content as ASCII. By default such strings are
+    /// rejected with `Error::ExpectedAsciiString`. In lenient mode they are instead
+    /// decoded as Latin-1 (ISO 8859-1), in which each byte is the code point of one
+    /// character. As Latin-1 is a superset of ASCII this never fails, although the
+    /// decoded string cannot be borrowed from the input.
+    pub fn with_lenient_ascii(mut self, lenient_ascii: bool) -> Self {
+        self.options.lenient_ascii = lenient_ascii;
+        self
+    }
+
+    /// Sets the maximum number of arrays and dictionaries which may be nested within
+    /// each other, which is 128 by default.
+    ///
+    /// Collections are decoded recursively, so without a limit a deeply nested document
+    /// could exhaust the stack. Exceeding the limit fails with
+    /// `Error::RecursionLimitExceeded`. This is independent of cycle detection.
+    pub fn with_recursion_limit(mut self, recursion_limit: usize) -> Self {
+        self.options.recursion_limit = recursion_limit;
+        self
+    }
+
+    /// Sets whether documents with a nonzero sort version in the trailer are rejected.
+    ///
+    /// CoreFoundation only writes sort version 0. Other values indicate that the keys
+    /// of dictionaries were ordered by an algorithm this library does not implement,
+    /// so the document may not be reproduced faithfully when re-encoded. By default
+    /// the sort version is ignored. In strict mode such documents fail to decode with
+    /// `Error::UnsupportedSortVersion`.
+    pub fn with_strict_sort_version(mut self, strict_sort_version: bool) -> Self {
+        self.options.strict_sort_version = strict_sort_version;
+        self
+    }
+
+    /// Sets whether integers and booleans may be decoded as each other.
+    ///
+    /// `NSNumber` represents both booleans and integers, and some encoders write a
+    /// boolean as the integer 0 or 1. By default a `bool` is only decoded from a
+    /// boolean object. In lenient mode an integer object with the value 0 or 1 is
+    /// decoded as `false` or `true`, and a boolean object is decoded into an integer
+    /// type as 0 or 1.
+    pub fn with_lenient_booleans(mut self, lenient_booleans: bool) -> Self {
+        self.options.lenient_booleans = lenient_booleans;
+        self
+    }
+
+    /// Sets whether the deserializer reports itself as human readable to the types it
+    /// decodes, which it does by default.
+    ///
+    /// Some types have both a readable and a compact representation. An `IpAddr`, for
+    /// example, is decoded from a string when human readable, but otherwise from its
+    /// compact enum form. Disabling this lets such types be decoded from the compact
+    /// representations written by other encoders, such as a `Uuid` from 16 bytes of data.
+    pub fn with_human_readable(mut self, human_readable: bool) -> Self {
+        self.options.human_readable = human_readable;
+        self
     }
 }
 
@@ -258,9 +468,194 @@ where
     T::deserialize(&mut deserializer)
 }
 
+/// Deserialize an instance of type `T` from a bplist document read from an I/O stream.
+///
+/// The trailer and offset table at the end of a document are needed to locate its
+/// objects, so the stream is read to completion before decoding. The decoded value
+/// cannot borrow from the input, which is discarded.
+///
+/// # Errors
+///
+/// This can fail for any of the reasons described in `from_slice`. Failures reading
+/// from the stream are reported as `Error::Io`.
+pub fn from_reader<R, T>(mut reader: R) -> Result<T>
+where
+    R: io::Read,
+    T: DeserializeOwned,
+{
+    let mut input = Vec::new();
+    reader.read_to_end(&mut input)?;
+    from_slice(&input)
+}
+
+/// Decode a bplist document into an `Object` tree.
+///
+/// This is the entry point for traversing a document dynamically, without declaring
+/// a type for its contents. It is equivalent to `from_slice::<Object>`.
+///
+/// # Example
+///
+/// ```
+/// // A bplist document containing `{ "x": 1, "y": 20 }`.
+/// let data = &[0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+///              0xD2, 0x01, 0x02, 0x03, 0x04, 0x51, 0x78, 0x51,
+///              0x79, 0x10, 0x01, 0x10, 0x14, 0x08, 0x0D, 0x0F,
+///              0x11, 0x13, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+///              0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+///              0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+///              0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+///              0x00, 0x15];
+///
+/// let object = bplist::to_object(data).unwrap();
+/// assert_eq!(object["y"].as_i64(), Some(20));
+/// ```
+///
+/// # Errors
+///
+/// This can fail for any of the reasons described in `from_slice`.
+pub fn to_object(input: &[u8]) -> Result<Object> {
+    from_slice(input)
+}
+
+/// Deserialize an instance of type `T` from a bplist document, along with a report
+/// of the objects in the document which are not reachable from the root object.
+///
+/// Unreachable objects are ignored by deserialization. They are typically left behind
+/// by tools which edit documents in place, and are of interest when auditing or
+/// migrating documents.
+///
+/// # Errors
+///
+/// This can fail for any of the reasons described in `from_slice`.
+pub fn from_slice_with_report<'a, T>(input: &'a [u8]) -> Result<(T, DocumentReport)>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = Deserializer::from_slice(input);
+    let value = T::deserialize(&mut deserializer)?;
+    let report = Archive::from_object_table(deserializer.take_object_table()?).report()?;
+    Ok((value, report))
+}
+
+/// Deserialize an instance of type `T` from a bplist document, along with the non-fatal
+/// anomalies found in the document.
+///
+/// Anomalies such as non-minimal integer encodings, oversized object references and
+/// unreachable objects do not affect the decoded value. They are of interest when
+/// linting documents, as CoreFoundation does not produce them.
+///
+/// # Errors
+///
+/// This can fail for any of the reasons described in `from_slice`.
+pub fn from_slice_with_warnings<'a, T>(input: &'a [u8]) -> Result<(T, Vec<Warning>)>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = Deserializer::from_slice(input);
+    let value = T::deserialize(&mut deserializer)?;
+    let warnings = Archive::from_object_table(deserializer.take_object_table()?).warnings()?;
+    Ok((value, warnings))
+}
+
+/// Deserialize an instance of type `T` from a bplist document, along with statistics
+/// about the objects referenced while decoding it.
+///
+/// # Errors
+///
+/// This can fail for any of the reasons described in `from_slice`.
+pub fn from_slice_with_stats<'a, T>(input: &'a [u8]) -> Result<(T, DecodeStats)>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = Deserializer::from_slice(input);
+    let value = T::deserialize(&mut deserializer)?;
+    Ok((value, deserializer.stats()))
+}
+
+/// Checks that a bplist document is well-formed without decoding it into values.
+///
+/// The header, trailer and offset table are validated, followed by every object
+/// reachable from the root object. This detects malformed objects, dangling
+/// references and cycles as `from_slice` would, but without allocating the decoded
+/// values, which makes it suitable for rejecting untrusted input early.
+///
+/// # Errors
+///
+/// Returns the first structural error encountered, as described in `from_slice`.
+pub fn validate(input: &[u8]) -> Result<()> {
+    Deserializer::from_slice(input).deserialize_root(|deserializer, _| deserializer.skip_object())
+}
+
+/// Returns whether a bplist document is a keyed archive, such as one produced by
+/// `NSKeyedArchiver`.
+///
+/// This decodes only the keys of the root dictionary, as described in
+/// `Archive::is_keyed_archive`, which makes it suitable for deciding how to decode
+/// the rest of the document.
+///
+/// # Errors
+///
+/// This can fail if the header, trailer or offset table of the document is missing
+/// or invalid, or if the root dictionary is malformed.
+pub fn is_keyed_archive(input: &[u8]) -> Result<bool> {
+    Archive::from_slice(input)?.is_keyed_archive()
+}
+
+/// Parses the header at the start of a bplist document.
+///
+/// The version in the header is returned as written, whether or not this library
+/// supports it.
+///
+/// # Errors
+///
+/// Returns `Error::Eof` if the input is shorter than a header, and
+/// `Error::MissingOrInvalidHeader` if the input does not begin with the bplist
+/// magic number.
+pub fn read_header(input: &[u8]) -> Result<Header> {
+    if input.len() < HEADER_SIZE {
+        return Err(Error::Eof);
+    }
+    parser::document::header(&input[0 .. HEADER_SIZE])
+        .map(|(_, header)| header)
+        .map_err(|_| Error::MissingOrInvalidHeader)
+}
+
+/// Parses the trailer at the end of a bplist document.
+///
+/// The trailer describes the number of objects in the document, the width of object
+/// references and the location of the offset table and root object. None of these
+/// are checked against the rest of the document.
+///
+/// # Errors
+///
+/// Returns `Error::Eof` if the input is shorter than a trailer, and
+/// `Error::MissingOrInvalidTrailer` if the trailer cannot be parsed.
+pub fn read_trailer(input: &[u8]) -> Result<Trailer> {
+    if input.len() < TRAILER_SIZE {
+        return Err(Error::Eof);
+    }
+    parser::document::trailer(&input[input.len() - TRAILER_SIZE ..])
+        .map(|(_, trailer)| trailer)
+        .map_err(|_| Error::MissingOrInvalidTrailer)
+}
+
+/// Parses the header, trailer and offset table of a bplist document without
+/// decoding any objects.
+///
+/// This exposes the metadata used to interpret the document, which is useful
+/// for debugging and tooling that reports on the structure of a file.
+///
+/// # Errors
+///
+/// This can fail if the header, trailer or offset table of the document is
+/// missing or invalid.
+pub fn inspect(input: &[u8]) -> Result<Inspection> {
+    Deserializer::from_slice(input).parse_document()
+}
+
 impl<'de> Deserializer<'de> {
 
-    /// Parses the metadata necessary to interpret the contents of the document.
+    /// Parses the header, trailer and offset table of the document.
     /// 
     /// # Notes
     /// 
@@ -269,40 +664,39 @@ impl<'de> Deserializer<'de> {
     ///   2. An object table with a single one-byte entry such as a zero-length array.
     ///   3. An offset table with a single one-byte entry for the root object.
     ///   4. A fixed-sized trailer.
-    fn parse_metadata(&self) -> Result<Metadata> {
+    fn parse_document(&self) -> Result<Inspection> {
         if self.input.len() < HEADER_SIZE + 2 + TRAILER_SIZE {
             return Err(Error::Eof);
         }
 
         // Parse the header and verify both the magic number and the version marker.
-        let header_slice = &self.input[0 .. HEADER_SIZE];
-        let (_, header) = parser::document::header(header_slice).map_err(|_| {
-            Error::MissingOrInvalidHeader
-        })?;
+        let header = read_header(self.input)?;
         if header.version != HEADER_VERSION_00 {
             return Err(Error::UnsupportedVersion);
         }
 
         // Parse the trailer from the end of the input and sanity check the fields.
-        let trailer_slice = &self.input[self.input.len() - TRAILER_SIZE .. ];
-        let (_, trailer) = parser::document::trailer(trailer_slice).map_err(|_| {
-            Error::MissingOrInvalidTrailer
-        })?;
+        let trailer = read_trailer(self.input)?;
         if trailer.root_object >= trailer.number_of_objects {
             return Err(Error::InvalidRootObject);
         }
 
         // Compute the location and length of the offset table.
         let offset_table_start = trailer.offset_table_offset;
-        let offset_table_length = trailer.number_of_objects * trailer.offset_table_entry_size;
+        let offset_table_length = trailer.number_of_objects
+            .checked_mul(trailer.offset_table_entry_size)
+            .ok_or(Error::MissingOrInvalidOffsetTable)?;
     
         // The offset table should not be defined as overlapping with the trailer.
-        if (offset_table_start + offset_table_length) > (self.input.len() - TRAILER_SIZE) {
+        let offset_table_end = offset_table_start
+            .checked_add(offset_table_length)
+            .ok_or(Error::MissingOrInvalidOffsetTable)?;
+        if offset_table_end > (self.input.len() - TRAILER_SIZE) {
             return Err(Error::MissingOrInvalidOffsetTable);
         }
 
         // Parse the offset table.
-        let offset_table_slice = &self.input[offset_table_start .. offset_table_start + offset_table_length];
+        let offset_table_slice = &self.input[offset_table_start .. offset_table_end];
         let (_, offset_table) = parser::document::offset_table(
             trailer.number_of_objects, 
             trailer.offset_table_entry_size
@@ -310,43 +704,172 @@ impl<'de> Deserializer<'de> {
             Error::MissingOrInvalidOffsetTable
         })?;
 
+        Ok(Inspection {
+            header,
+            trailer,
+            offset_table,
+        })
+    }
+
+    /// Parses the metadata necessary to interpret the contents of the document.
+    fn parse_metadata(&self) -> Result<Metadata> {
+        let Inspection { trailer, offset_table, .. } = self.parse_document()?;
+
+        // Objects reside between the header and the offset table, so there must be room.
+        let object_table_range = HEADER_SIZE .. trailer.offset_table_offset;
+        if object_table_range.is_empty() && trailer.number_of_objects > 0 {
+            return Err(Error::MissingOrInvalidObjectTable);
+        }
+
+        if self.options.strict_sort_version && trailer.sort_version != 0 {
+            return Err(Error::UnsupportedSortVersion);
+        }
+
         Ok(Metadata {
             offset_table,
             object_reference_size: trailer.object_reference_size,
             root_object: trailer.root_object,
-            object_table_range: (HEADER_SIZE .. offset_table_start)
+            object_table_range,
+            sort_version: trailer.sort_version,
         })
     }
 
-}
+    /// Returns the object table, parsing it if it has not been parsed with the current
+    /// options.
+    fn object_table(&mut self) -> Result<&ObjectTable<'de>> {
+        let object_table = self.take_object_table()?;
+        Ok(self.object_table.insert(object_table))
+    }
 
-impl<'de, 'b> de::Deserializer<'de> for &'b mut Deserializer<'de> {
-    type Error = Error;
+    /// Removes and returns the object table, parsing it if it has not been parsed with
+    /// the current options.
+    fn take_object_table(&mut self) -> Result<ObjectTable<'de>> {
+        match self.object_table.take() {
+            Some(object_table) if object_table.options == self.options => Ok(object_table),
+            _ => self.parse_object_table(),
+        }
+    }
 
-    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
-    where
-        V: de::Visitor<'de>,
-    {
-        // Parse the metadata and use it to create an object table.
+    /// Parses the metadata and uses it to create an object table.
+    fn parse_object_table(&self) -> Result<ObjectTable<'de>> {
         let metadata = self.parse_metadata()?;
-        let object_table = ObjectTable {
-            metadata,
+        Ok(ObjectTable {
             input: self.input,
-        };
+            formats: (0 .. metadata.offset_table.len()).map(|_| AtomicU8::new(0)).collect(),
+            metadata,
+            options: self.options,
+        })
+    }
+
+    /// Deserializes the root object, which must be an Array or Dictionary, with `f`,
+    /// retaining the statistics gathered.
+    fn deserialize_root<F, T>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut ObjectDeserializer<'_, 'de>, ObjectFormat) -> Result<T>,
+    {
+        let object_table = self.object_table()?;
 
         // Verify the root object is an Array or Dictionary.
         let root_object = object_table.metadata.root_object;
         let root_object_type = object_table.kind_of(root_object)?;
+        if root_object_type != ObjectFormat::Array && root_object_type != ObjectFormat::Dictionary {
+            return Err(Error::RootObjectNotArrayOrDictionary);
+        }
 
-        // Create a deserializer for the root object and forward the call.
         let mut deserializer = ObjectDeserializer::new(object_table, root_object);
-        if root_object_type == ObjectFormat::Array {
+        let result = f(&mut deserializer, root_object_type);
+        self.stats = deserializer.stats;
+        result
+    }
+
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_root(|deserializer, root_object_type| {
+            if root_object_type == ObjectFormat::Array {
+                deserializer.deserialize_seq(visitor)
+            } else {
+                deserializer.deserialize_map(visitor)
+            }
+        })
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Report a mismatched root directly, rather than as a visitor type error.
+        self.deserialize_root(|deserializer, root_object_type| {
+            if root_object_type != ObjectFormat::Array {
+                return Err(Error::Message(String::from("expected array root, found dictionary")));
+            }
             deserializer.deserialize_seq(visitor)
-        } else if root_object_type == ObjectFormat::Dictionary {
+        })
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_root(|deserializer, root_object_type| {
+            if root_object_type != ObjectFormat::Dictionary {
+                return Err(Error::Message(String::from("expected dictionary root, found array")));
+            }
             deserializer.deserialize_map(visitor)
-        } else {
-            Err(Error::RootObjectNotArrayOrDictionary)
-        }
+        })
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_root(|deserializer, _| {
+            deserializer.deserialize_struct(name, fields, visitor)
+        })
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_root(|deserializer, _| deserializer.deserialize_ignored_any(visitor))
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_root(|deserializer, _| {
+            deserializer.deserialize_enum(name, variants, visitor)
+        })
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // The root object is always present, so a document never represents `None`.
+        visitor.visit_some(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.options.human_readable
     }
 
     serde::forward_to_deserialize_any! {
@@ -355,75 +878,178 @@ impl<'de, 'b> de::Deserializer<'de> for &'b mut Deserializer<'de> {
         i8 i16 i32 i64 i128
         f32 f64
         char str string
-        seq map
         bytes byte_buf
-        enum
-        struct
         unit unit_struct
         tuple tuple_struct
         newtype_struct
-        ignored_any
-        option
         identifier
     }
 
 }
 
 #[derive(Debug)]
-pub struct ObjectDeserializer<'de> {
-    /// The bytes which represent the totality of the input document.
-    object_table: ObjectTable<'de>,
+pub struct ObjectDeserializer<'t, 'de> {
+    /// The object table of the document, shared by every deserializer of its objects.
+    object_table: &'t ObjectTable<'de>,
     /// The index of the next object to process.
     next_object: usize,
-    /// Ordered set of the collections being processed to detect cycles.
-    collection_stack: BTreeSet<usize>,
+    /// Stack of the collections being processed, innermost last, to detect cycles.
+    collection_stack: Vec<usize>,
+    /// Whether each object in the offset table has been referenced, to detect sharing.
+    referenced_objects: Vec<bool>,
+    /// Statistics gathered while deserializing.
+    stats: DecodeStats,
 }
 
-impl<'de> ObjectDeserializer<'de> {
+impl<'t, 'de> ObjectDeserializer<'t, 'de> {
 
     /// Returns a new instance of the receiver for the specified object table and object.
-    fn new(object_table: ObjectTable<'de>, next_object: usize) -> Self {
+    fn new(object_table: &'t ObjectTable<'de>, next_object: usize) -> Self {
+        let mut referenced_objects = vec![false; object_table.metadata.offset_table.len()];
+        if let Some(referenced) = referenced_objects.get_mut(next_object) {
+            *referenced = true;
+        }
         ObjectDeserializer { 
             object_table,
             next_object,
-            collection_stack: BTreeSet::new(),
+            collection_stack: Vec::new(),
+            referenced_objects,
+            stats: DecodeStats::default(),
         }
     }
 
-    /// Sets the next object to process.
+    /// Sets the next object to process, counting references to shared objects.
     fn set_next_object(&mut self, object: usize) {
+        if let Some(referenced) = self.referenced_objects.get_mut(object) {
+            if *referenced {
+                self.stats.shared_object_references += 1;
+            }
+            *referenced = true;
+        }
         self.next_object = object
     }
 
-    /// Pushes an object onto the collection stack to ensure no cycles can occur.
+    /// Pushes an object onto the collection stack to ensure no cycles can occur and
+    /// the recursion limit is not exceeded.
     #[must_use = "the result must be checked to avoid creating a cycle"]
     fn enter_collection(&mut self, object: usize) -> Result<()> {
-        if self.collection_stack.len() == 127 {
-            Err(Error::MaximumDepthExceeded)
-        } else if self.collection_stack.insert(object) == false {
+        if self.collection_stack.len() >= self.object_table.options.recursion_limit {
+            Err(Error::RecursionLimitExceeded)
+        } else if self.collection_stack.contains(&object) {
             Err(Error::CycleDetected)
         } else {
+            self.collection_stack.push(object);
             Ok(())
         }
     }
 
     /// Pops the most recently entered collection from the stack.
     fn exit_collection(&mut self) {
-        assert!(self.collection_stack.is_empty() == false, "unbalanced calls in object stack tracking");
-        let value = self.collection_stack
-            .iter()
-            .cloned()
-            .next_back()
-            .unwrap();
-        self.collection_stack.remove(&value);
+        let exited = self.collection_stack.pop();
+        assert!(exited.is_some(), "unbalanced calls in object stack tracking");
+    }
+
+    /// Deserializes the next object for an integer type.
+    ///
+    /// In lenient boolean mode, a boolean object is visited as the integer 0 or 1.
+    fn deserialize_integer<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let object = self.next_object;
+        if self.object_table.options.lenient_booleans
+            && self.object_table.kind_of(object)? == ObjectFormat::Boolean
+        {
+            return visitor.visit_u8(u8::from(self.object_table.parse_boolean(object)?));
+        }
+        de::Deserializer::deserialize_any(self, visitor)
     }
 
+    /// Deserializes the next object, which is a dictionary, through a map access object.
+    ///
+    /// If `string_keys` is set, a key which is not a string fails as it is visited.
+    fn deserialize_dictionary<V>(&mut self, visitor: V, string_keys: bool) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let object = self.next_object;
+        let pairs = self.object_table.parse_dictionary(object)?;
+
+        // Track the entering the dictionary to detect reference cycles.
+        self.enter_collection(object)?;
+        let dictionary_map = DictionaryMap::new(self, pairs, string_keys);
+        let result = visitor.visit_map(dictionary_map);
+        self.exit_collection();
+        result
+    }
+
+    /// Validates the next object and every object it contains without producing values.
+    fn skip_object(&mut self) -> Result<()> {
+        let object = self.next_object;
+        match self.object_table.kind_of(object)? {
+            ObjectFormat::Null => { self.object_table.parse_null(object)?; }
+            ObjectFormat::Boolean => { self.object_table.parse_boolean(object)?; }
+            ObjectFormat::Fill => { self.object_table.parse_fill(object)?; }
+            ObjectFormat::UInt8 => { self.object_table.parse_uint8(object)?; }
+            ObjectFormat::UInt16 => { self.object_table.parse_uint16(object)?; }
+            ObjectFormat::UInt32 => { self.object_table.parse_uint32(object)?; }
+            ObjectFormat::SInt64 => { self.object_table.parse_sint64(object)?; }
+            ObjectFormat::SInt128 => { self.object_table.parse_sint128(object)?; }
+            ObjectFormat::Float32 => { self.object_table.parse_float32(object)?; }
+            ObjectFormat::Float64 => { self.object_table.parse_float64(object)?; }
+            ObjectFormat::Date => { self.object_table.parse_date(object)?; }
+            ObjectFormat::Data => { self.object_table.parse_data(object)?; }
+            ObjectFormat::AsciiString => { self.object_table.parse_ascii_text(object)?; }
+            ObjectFormat::Utf16String => { self.object_table.parse_utf16_string(object)?; }
+            ObjectFormat::Uid => { self.object_table.parse_uid(object)?; }
+
+            ObjectFormat::Array => {
+                let objects = self.object_table.parse_array(object)?;
+                self.enter_collection(object)?;
+                let result = objects.into_iter().try_for_each(|element| {
+                    self.set_next_object(element);
+                    self.skip_object()
+                });
+                self.exit_collection();
+                result?;
+            }
+
+            ObjectFormat::Dictionary => {
+                let pairs = self.object_table.parse_dictionary(object)?;
+                self.enter_collection(object)?;
+                let result = pairs.into_iter().try_for_each(|(key, value)| {
+                    self.set_next_object(key);
+                    self.skip_object()?;
+                    self.set_next_object(value);
+                    self.skip_object()
+                });
+                self.exit_collection();
+                result?;
+            }
+        }
+        Ok(())
+    }
+
+}
+
+/// Implements deserialization methods for integer types in terms of `deserialize_integer`.
+macro_rules! deserialize_integers {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value>
+            where
+                V: de::Visitor<'de>,
+            {
+                self.deserialize_integer(visitor)
+            }
+        )*
+    };
 }
 
-impl<'de, 'b> de::Deserializer<'de> for &'b mut ObjectDeserializer<'de> {
+impl<'t, 'de> de::Deserializer<'de> for &mut ObjectDeserializer<'t, 'de> {
     type Error = Error;
 
-    fn deserialize_any<V>(mut self, visitor: V) -> Result<V::Value>
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
@@ -444,20 +1070,39 @@ impl<'de, 'b> de::Deserializer<'de> for &'b mut ObjectDeserializer<'de> {
                 visitor.visit_u32(self.object_table.parse_uint32(object)?),
             ObjectFormat::SInt64 =>
                 visitor.visit_i64(self.object_table.parse_sint64(object)?),
+            ObjectFormat::SInt128 => {
+                // Values are visited as the narrowest integer type which can represent
+                // them, as visitors for 64-bit integers need not accept 128-bit values.
+                let value = self.object_table.parse_sint128(object)?;
+                if let Ok(value) = i64::try_from(value) {
+                    visitor.visit_i64(value)
+                } else if let Ok(value) = u64::try_from(value) {
+                    visitor.visit_u64(value)
+                } else {
+                    visitor.visit_i128(value)
+                }
+            }
             ObjectFormat::Float32 =>
                 visitor.visit_f32(self.object_table.parse_float32(object)?),
             ObjectFormat::Float64 =>
                 visitor.visit_f64(self.object_table.parse_float64(object)?),
             ObjectFormat::Data =>
-                visitor.visit_bytes(self.object_table.parse_data(object)?),
+                visitor.visit_borrowed_bytes(self.object_table.parse_data(object)?),
             ObjectFormat::AsciiString =>
-                visitor.visit_str(self.object_table.parse_ascii_string(object)?),
+                match self.object_table.parse_ascii_text(object)? {
+                    Cow::Borrowed(value) => visitor.visit_borrowed_str(value),
+                    Cow::Owned(value) => visitor.visit_string(value),
+                },
             ObjectFormat::Utf16String =>
                 visitor.visit_string(self.object_table.parse_utf16_string(object)?),
 
-            // Fill bytes are interpreted as unit values.
+            // Null objects and fill bytes are interpreted as unit values.
+            ObjectFormat::Null => {
+                self.object_table.parse_null(object)?;
+                visitor.visit_unit()
+            }
             ObjectFormat::Fill => {
-                let _ = self.object_table.parse_fill(object)?;
+                self.object_table.parse_fill(object)?;
                 visitor.visit_unit()
             }
 
@@ -481,54 +1126,188 @@ impl<'de, 'b> de::Deserializer<'de> for &'b mut ObjectDeserializer<'de> {
 
                 // Track entering the array to detect reference cycles.
                 self.enter_collection(object)?;
-                let array_seq = ArraySeq::new(&mut self, objects);
+                let array_seq = ArraySeq::new(self, objects);
                 let result = visitor.visit_seq(array_seq);
                 self.exit_collection();
                 result
             }
 
             // Dictionaries are processed through a map access object.
+            ObjectFormat::Dictionary =>
+                self.deserialize_dictionary(visitor, false),
+        }
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Report a mismatched object directly rather than visiting it as another type.
+        let object = self.next_object;
+        let format = self.object_table.kind_of(object)?;
+        if format == ObjectFormat::Boolean {
+            return visitor.visit_bool(self.object_table.parse_boolean(object)?);
+        }
+
+        // In lenient mode, the integers 0 and 1 are accepted as booleans.
+        if self.object_table.options.lenient_booleans {
+            let value = match format {
+                ObjectFormat::UInt8 => Some(u64::from(self.object_table.parse_uint8(object)?)),
+                ObjectFormat::UInt16 => Some(u64::from(self.object_table.parse_uint16(object)?)),
+                ObjectFormat::UInt32 => Some(u64::from(self.object_table.parse_uint32(object)?)),
+                ObjectFormat::SInt64 => Some(self.object_table.parse_sint64(object)? as u64),
+                _ => None,
+            };
+            match value {
+                Some(0) => return visitor.visit_bool(false),
+                Some(1) => return visitor.visit_bool(true),
+                _ => {}
+            }
+        }
+        Err(Error::ExpectedBool)
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Only single-precision reals are accepted, as double-precision values would
+        // lose precision.
+        let object = self.next_object;
+        if self.object_table.kind_of(object)? != ObjectFormat::Float32 {
+            return Err(Error::ExpectedFloat32);
+        }
+        visitor.visit_f32(self.object_table.parse_float32(object)?)
+    }
+
+    fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Eight-byte integers are signed in the encoding, but often carry unsigned counts
+        // which exceed `i64::MAX`. An unsigned target receives the raw bits unchanged, so
+        // a negative value decodes as its two's complement rather than failing.
+        let object = self.next_object;
+        if self.object_table.kind_of(object)? == ObjectFormat::SInt64 {
+            return visitor.visit_u64(self.object_table.parse_sint64(object)? as u64);
+        }
+        self.deserialize_integer(visitor)
+    }
+
+    deserialize_integers! {
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u128
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Null and fill objects represent the absence of a value, as written for `None`.
+        let object = self.next_object;
+        match self.object_table.kind_of(object)? {
+            ObjectFormat::Null => {
+                self.object_table.parse_null(object)?;
+                visitor.visit_none()
+            }
+            ObjectFormat::Fill => {
+                self.object_table.parse_fill(object)?;
+                visitor.visit_none()
+            }
+            _ =>
+                visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Fields are identified by name, so a dictionary with any other kind of key
+        // cannot represent a structure.
+        if self.object_table.kind_of(self.next_object)? == ObjectFormat::Dictionary {
+            self.deserialize_dictionary(visitor, true)
+        } else {
+            self.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // The object is validated, but no values are produced for the visitor to discard.
+        self.skip_object()?;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Enums are externally tagged. A unit variant is represented by its name, and
+        // any other variant by a dictionary with a single entry from its name to its content.
+        let object = self.next_object;
+        match self.object_table.kind_of(object)? {
+            ObjectFormat::AsciiString | ObjectFormat::Utf16String =>
+                visitor.visit_enum(Enum::new(self, None)),
             ObjectFormat::Dictionary => {
                 let pairs = self.object_table.parse_dictionary(object)?;
+                if pairs.len() != 1 {
+                    return Err(de::Error::invalid_length(
+                        pairs.len(),
+                        &"a dictionary with a single entry"
+                    ));
+                }
 
                 // Track the entering the dictionary to detect reference cycles.
                 self.enter_collection(object)?;
-                let dictionary_map = DictionaryMap::new(&mut self, pairs);
-                let result = visitor.visit_map(dictionary_map);
+                let result = visitor.visit_enum(Enum::new(self, Some(pairs[0])));
                 self.exit_collection();
                 result
             }
+            format =>
+                Err(Error::Message(
+                    format!("expected string or dictionary for enum, found {} at object {}", format, object)
+                )),
         }
     }
 
+    fn is_human_readable(&self) -> bool {
+        self.object_table.options.human_readable
+    }
+
     serde::forward_to_deserialize_any! {
-        bool
-        u8 u16 u32 u64 u128
-        i8 i16 i32 i64 i128
-        f32 f64
+        f64
         char str string
         seq map
         bytes byte_buf
-        enum
-        struct
         unit unit_struct
         tuple tuple_struct
         newtype_struct
-        ignored_any
-        option
         identifier
     }
 
 }
 
 /// Access object to process the elements in an Array.
-struct ArraySeq<'a, 'de: 'a> {
-    de: &'a mut ObjectDeserializer<'de>,
+struct ArraySeq<'a, 't, 'de> {
+    de: &'a mut ObjectDeserializer<'t, 'de>,
     objects: vec::IntoIter<usize>,
 }
 
-impl<'a, 'de> ArraySeq<'a, 'de> {
-    fn new(de: &'a mut ObjectDeserializer<'de>, object_list: Vec<usize>) -> Self {
+impl<'a, 't, 'de> ArraySeq<'a, 't, 'de> {
+    fn new(de: &'a mut ObjectDeserializer<'t, 'de>, object_list: Vec<usize>) -> Self {
         ArraySeq {
             de,
             objects: object_list.into_iter()
@@ -536,7 +1315,7 @@ impl<'a, 'de> ArraySeq<'a, 'de> {
     }
 }
 
-impl<'de, 'a> SeqAccess<'de> for ArraySeq<'a, 'de> {
+impl<'de, 'a, 't> SeqAccess<'de> for ArraySeq<'a, 't, 'de> {
     type Error = Error;
 
     fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
@@ -553,23 +1332,30 @@ impl<'de, 'a> SeqAccess<'de> for ArraySeq<'a, 'de> {
 }
 
 /// Access object used to process the elements in a Dictionary.
-struct DictionaryMap<'a, 'de: 'a> {
-    de: &'a mut ObjectDeserializer<'de>,
+struct DictionaryMap<'a, 't, 'de> {
+    de: &'a mut ObjectDeserializer<'t, 'de>,
     key_value_pairs: vec::IntoIter<(usize, usize)>,
     current_pair: Option<(usize, usize)>,
+    /// Whether every key must be a string, as when the keys are the fields of a structure.
+    string_keys: bool,
 }
 
-impl<'a, 'de> DictionaryMap<'a, 'de> {
-    fn new(de: &'a mut ObjectDeserializer<'de>, list: Vec<(usize, usize)>) -> Self {
+impl<'a, 't, 'de> DictionaryMap<'a, 't, 'de> {
+    fn new(
+        de: &'a mut ObjectDeserializer<'t, 'de>,
+        list: Vec<(usize, usize)>,
+        string_keys: bool,
+    ) -> Self {
         DictionaryMap {
             de,
             key_value_pairs: list.into_iter(),
             current_pair: None,
+            string_keys,
         }
     }
 }
 
-impl<'de, 'a> MapAccess<'de> for DictionaryMap<'a, 'de> {
+impl<'de, 'a, 't> MapAccess<'de> for DictionaryMap<'a, 't, 'de> {
     type Error = Error;
 
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
@@ -581,6 +1367,14 @@ impl<'de, 'a> MapAccess<'de> for DictionaryMap<'a, 'de> {
 
         // Point the deserializer at the key and deserialize it.
         if let Some((key, _)) = self.current_pair {
+            if self.string_keys {
+                let format = self.de.object_table.kind_of(key)?;
+                if format != ObjectFormat::AsciiString && format != ObjectFormat::Utf16String {
+                    return Err(Error::Message(
+                        format!("expected string key, found {} at object {}", format, key)
+                    ));
+                }
+            }
              self.de.set_next_object(key);
              seed.deserialize(&mut *self.de).map(Some)
         } else {
@@ -599,14 +1393,159 @@ impl<'de, 'a> MapAccess<'de> for DictionaryMap<'a, 'de> {
     }
 }
 
+/// Access object used to process an externally tagged enum variant.
+///
+/// The variant is either the current object, a string naming a unit variant, or the
+/// single entry of a dictionary from the name of the variant to its content.
+struct Enum<'a, 't, 'de> {
+    de: &'a mut ObjectDeserializer<'t, 'de>,
+    entry: Option<(usize, usize)>,
+}
+
+impl<'a, 't, 'de> Enum<'a, 't, 'de> {
+    fn new(de: &'a mut ObjectDeserializer<'t, 'de>, entry: Option<(usize, usize)>) -> Self {
+        Enum {
+            de,
+            entry,
+        }
+    }
+
+    /// Points the deserializer at the content of the variant, which a unit variant lacks.
+    fn content(&mut self, expected: &str) -> Result<&mut ObjectDeserializer<'t, 'de>> {
+        match self.entry {
+            Some((_, value)) => {
+                self.de.set_next_object(value);
+                Ok(&mut *self.de)
+            }
+            None =>
+                Err(de::Error::invalid_type(Unexpected::UnitVariant, &expected)),
+        }
+    }
+}
+
+impl<'de, 'a, 't> EnumAccess<'de> for Enum<'a, 't, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        // Point the deserializer at the name of the variant and deserialize it.
+        if let Some((key, _)) = self.entry {
+            self.de.set_next_object(key);
+        }
+        let variant = seed.deserialize(&mut *self.de)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, 'a, 't> VariantAccess<'de> for Enum<'a, 't, 'de> {
+    type Error = Error;
+
+    fn unit_variant(mut self) -> Result<()> {
+        // A unit variant may also be written as a dictionary with null content.
+        if self.entry.is_some() {
+            de::Deserialize::deserialize(self.content("unit variant")?)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn newtype_variant_seed<T>(mut self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.content("newtype variant")?)
+    }
+
+    fn tuple_variant<V>(mut self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_seq(self.content("tuple variant")?, visitor)
+    }
+
+    fn struct_variant<V>(
+        mut self,
+        fields: &'static [&'static str],
+        visitor: V
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_struct(self.content("struct variant")?, "", fields, visitor)
+    }
+}
+
+/// Name of the newtype structure used to probe a map key for a pseudo-structure field.
+pub(crate) const PRIVATE_FIELD_TOKEN: &str = "$__bplist_private_field";
+
+/// Deserializer for the field name of a Date-type or UID-type pseudo-structure.
+///
+/// The field name is presented as a string. When probed with a newtype structure
+/// named `PRIVATE_FIELD_TOKEN`, the field name is instead presented as a unit enum
+/// variant. This allows the field to be distinguished from a dictionary key which
+/// happens to have the same value.
+struct PrivateFieldDeserializer {
+    field: &'static str,
+}
+
+impl PrivateFieldDeserializer {
+    fn new(field: &'static str) -> Self {
+        PrivateFieldDeserializer {
+            field,
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for PrivateFieldDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_str(self.field)
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if name == PRIVATE_FIELD_TOKEN {
+            visitor.visit_enum(self.field.into_deserializer())
+        } else {
+            self.deserialize_any(visitor)
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool
+        u8 u16 u32 u64 u128
+        i8 i16 i32 i64 i128
+        f32 f64
+        char str string
+        seq map
+        bytes byte_buf
+        enum
+        struct
+        unit unit_struct
+        tuple tuple_struct
+        ignored_any
+        option
+        identifier
+    }
+}
+
 /// Access object to provide a Map around a Date-type pseudo-structure.
-struct DateMap {
+pub(crate) struct DateMap {
     visited: bool,
     absolute_time: f64,
 }
 
 impl DateMap {
-    fn new(absolute_time: f64) -> Self {
+    pub(crate) fn new(absolute_time: f64) -> Self {
         DateMap {
             absolute_time,
             visited: false,
@@ -624,7 +1563,7 @@ impl<'de> de::MapAccess<'de> for DateMap {
             Ok(None)
         } else {
             self.visited = true;
-            seed.deserialize(date::STRUCT_FIELD.into_deserializer()).map(Some)
+            seed.deserialize(PrivateFieldDeserializer::new(date::STRUCT_FIELD)).map(Some)
         }
     }
 
@@ -636,13 +1575,13 @@ impl<'de> de::MapAccess<'de> for DateMap {
 }
 
 /// Access object to provide a Map around a UID-type pseudo-structure.
-struct UidMap {
+pub(crate) struct UidMap {
     visited: bool,
     uid: u64,
 }
 
 impl UidMap {
-    fn new(uid: u64) -> Self {
+    pub(crate) fn new(uid: u64) -> Self {
         UidMap {
             uid,
             visited: false,
@@ -660,7 +1599,7 @@ impl<'de> de::MapAccess<'de> for UidMap {
             Ok(None)
         } else {
             self.visited = true;
-            seed.deserialize(uid::STRUCT_FIELD.into_deserializer()).map(Some)
+            seed.deserialize(PrivateFieldDeserializer::new(uid::STRUCT_FIELD)).map(Some)
         }
     }
 
@@ -11,12 +11,11 @@ use nom::{
     IResult,
     bytes::complete::{take, tag},
     combinator::map,
-    multi::many_m_n,
     number::complete::be_u8,
     sequence::tuple,
 };
 
-use crate::de::parser::utils::be_usize_n;
+use crate::de::parser::utils::{be_usize_n, bounded_many};
 use crate::document::{
     Header,
     OffsetTable,
@@ -50,9 +49,9 @@ pub fn offset_table(
     entry_size: usize,
 ) -> impl Fn(&[u8]) -> IResult<&[u8], OffsetTable> {
     move |input: &[u8]| {
-        many_m_n(
-            entries,
+        bounded_many(
             entries,
+            entry_size,
             be_usize_n(entry_size)
         )(input)
     }
@@ -13,8 +13,7 @@ use nom::{
     branch::alt,
     bytes::complete::take,
     combinator::{map, map_res, verify},
-    multi::many_m_n,
-    number::complete::{be_u8, be_u16, be_u32, be_i64, be_f32, be_f64},
+    number::complete::{be_u8, be_u16, be_u32, be_i64, be_i128, be_f32, be_f64},
     sequence::tuple,
 };
 
@@ -22,7 +21,7 @@ use std::convert::TryFrom;
 use std::string::FromUtf16Error;
 
 use crate::document::ObjectFormat;
-use crate::de::parser::utils::{be_usize_n, be_u64_n};
+use crate::de::parser::utils::{be_usize_n, be_u64_n, bounded_many};
 
 /// Returns a parser which consumes a marker conforming to the specified format.
 /// On success, the parser yields both the validated format and the encoded value.
@@ -46,12 +45,14 @@ fn marker(
 /// Parses a marker byte and returns both the object format and encoded value.
 pub fn any_marker(input: &[u8]) -> IResult<&[u8], (ObjectFormat, u8)> {
     alt((
+        marker(ObjectFormat::Null),
         marker(ObjectFormat::Boolean),
         marker(ObjectFormat::Fill),
         marker(ObjectFormat::UInt8),
         marker(ObjectFormat::UInt16),
         marker(ObjectFormat::UInt32),
         marker(ObjectFormat::SInt64),
+        marker(ObjectFormat::SInt128),
         marker(ObjectFormat::Float32),
         marker(ObjectFormat::Float64),
         marker(ObjectFormat::Date),
@@ -72,6 +73,14 @@ pub fn boolean(input: &[u8]) -> IResult<&[u8], bool> {
     )(input)
 }
 
+/// Parses a null object, which is represented as a unit type.
+pub fn null(input: &[u8]) -> IResult<&[u8], ()> {
+    map(
+        marker(ObjectFormat::Null),
+        |_| ()
+    )(input)
+}
+
 /// Parses a fill object, which is represented as a unit type.
 pub fn fill(input: &[u8]) -> IResult<&[u8], ()> {
     map(
@@ -124,6 +133,17 @@ pub fn sint64(input: &[u8]) -> IResult<&[u8], i64> {
     )(input)
 }
 
+/// Parses a 128-bit signed integer object.
+pub fn sint128(input: &[u8]) -> IResult<&[u8], i128> {
+    map(
+        tuple((
+            marker(ObjectFormat::SInt128),
+            be_i128,
+        )),
+        |(_, value)| value
+    )(input)
+}
+
 /// Parses a 32-bit single-precision floating point value.
 pub fn float32(input: &[u8]) -> IResult<&[u8], f32> {
     map(
@@ -179,7 +199,7 @@ fn payload_count(
                     map(uint32, |value| value as u64),
                     map(sint64, |value| value as u64),
                 )),
-                |value| usize::try_from(value)
+                usize::try_from
             )(input)
         } else {
             Ok((input, encoded_value as usize))
@@ -211,6 +231,22 @@ pub fn ascii_string(input: &[u8]) -> IResult<&[u8], &str> {
     )(input)
 }
 
+/// Parses a variable-length ASCII string object whose contents are interpreted as
+/// Latin-1 (ISO 8859-1) and returns an owned string.
+///
+/// # Notes
+///
+/// 1. Each byte is the code point of one character, so this never fails on content.
+/// 2. This is not a zero-copy operation.
+pub fn latin1_string(input: &[u8]) -> IResult<&[u8], String> {
+    let (input, (_, encoded_value)) = marker(ObjectFormat::AsciiString)(input)?;
+    let (input, char_count) = payload_count(encoded_value)(input)?;
+    map(
+        take(char_count),
+        |bytes: &[u8]| bytes.iter().map(|&byte| char::from(byte)).collect()
+    )(input)
+}
+
 /// Parses a variable-length UTF-16 string object and returns an owned string.
 ///
 /// # Notes
@@ -221,9 +257,9 @@ pub fn utf16_string(input: &[u8]) -> IResult<&[u8], String> {
     let (input, (_, encoded_value)) = marker(ObjectFormat::Utf16String)(input)?;
     let (input, char_count) = payload_count(encoded_value)(input)?;
     map_res(
-        many_m_n(
-            char_count, 
-            char_count, 
+        bounded_many(
+            char_count,
+            2,
             be_u16
         ), |code_points| -> Result<String, FromUtf16Error> {
             String::from_utf16(&code_points)
@@ -255,30 +291,40 @@ pub fn array(
     move |input: &[u8]| {
         let (input, (_, encoded_value)) = marker(ObjectFormat::Array)(input)?;
         let (input, array_length) = payload_count(encoded_value)(input)?;
-        many_m_n(
-            array_length, 
-            array_length, 
+        bounded_many(
+            array_length,
+            object_reference_size,
             be_usize_n(object_reference_size)
         )(input)
     }
 }
 
+/// Parses the marker and length of a dictionary, returning the number of entries.
+///
+/// The remaining input begins with the key references, which are followed by the
+/// same number of value references.
+pub fn dictionary_entry_count(input: &[u8]) -> IResult<&[u8], usize> {
+    let (input, (_, encoded_value)) = marker(ObjectFormat::Dictionary)(input)?;
+    payload_count(encoded_value)(input)
+}
+
+/// A list of matched key and value object references.
+pub type KeyValueReferences = Vec<(usize, usize)>;
+
 /// Returns a parser for a dictionary with the specified-width key and value references.
 ///
 /// The value returned by the parser is a list of matched key and value object references.
 /// In each touple, the key is first and the value is second.
 pub fn dictionary(
     object_reference_size: usize
-) -> impl Fn(&[u8]) -> IResult<&[u8], Vec<(usize, usize)>> {
+) -> impl Fn(&[u8]) -> IResult<&[u8], KeyValueReferences> {
     assert!(object_reference_size <= 8, "object references must be up to 8 bytes long");
     move |input: &[u8]| {
-        let (input, (_, encoded_value)) = marker(ObjectFormat::Dictionary)(input)?;
-        let (input, entry_count) = payload_count(encoded_value)(input)?;
-        
+        let (input, entry_count) = dictionary_entry_count(input)?;
         map(
             tuple((
-                many_m_n(entry_count, entry_count, be_usize_n(object_reference_size)),
-                many_m_n(entry_count, entry_count, be_usize_n(object_reference_size)),
+                bounded_many(entry_count, object_reference_size, be_usize_n(object_reference_size)),
+                bounded_many(entry_count, object_reference_size, be_usize_n(object_reference_size)),
             )), |(
                 mut keys, 
                 mut values
@@ -286,7 +332,7 @@ pub fn dictionary(
                 // Interleave the key and value references
                 keys.drain(..)
                     .zip(values.drain(..))
-                    .collect::<Vec<(usize, usize)>>()
+                    .collect::<KeyValueReferences>()
             }
         )(input)
     }
@@ -295,10 +341,12 @@ pub fn dictionary(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use nom::multi::many_m_n;
 
     #[test]
     fn test_any_marker_valid() {
         let test_markers = &[
+            0b0000_0000, // Null
             0b0000_1000, // Boolean (false)
             0b0000_1001, // Boolean (true)
             0b0000_1111, // Fill
@@ -306,6 +354,7 @@ mod tests {
             0b0001_0001, // UInt16
             0b0001_0010, // UInt32
             0b0001_0011, // SInt64
+            0b0001_0100, // SInt128
             0b0010_0010, // Float32
             0b0010_0011, // Float64
             0b0011_0011, // Date
@@ -328,6 +377,7 @@ mod tests {
             0b1101_1111, // Dictionary (extended payload)
         ];
         let expected_results = &[
+            (ObjectFormat::Null, 0),
             (ObjectFormat::Boolean, 0b0000),
             (ObjectFormat::Boolean, 0b0001),
             (ObjectFormat::Fill, 0),
@@ -335,6 +385,7 @@ mod tests {
             (ObjectFormat::UInt16, 0),
             (ObjectFormat::UInt32, 0),
             (ObjectFormat::SInt64, 0),
+            (ObjectFormat::SInt128, 0),
             (ObjectFormat::Float32, 0),
             (ObjectFormat::Float64, 0),
             (ObjectFormat::Date, 0),
@@ -386,6 +437,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_null() {
+        let test_input = &[
+            // Null
+            0b0000_0000,
+            // Null
+            0b0000_0000,
+        ];
+        let expected_output = vec![
+            (),
+            (),
+        ];
+        let count = expected_output.len();
+        assert_eq!(
+            many_m_n(count, count, null)(test_input),
+            Ok((
+                &test_input[test_input.len() .. ],
+                expected_output,
+            ))
+        );
+        assert!(fill(test_input).is_err());
+    }
+
     #[test]
     fn test_fill() {
         let test_input = &[
@@ -512,6 +586,35 @@ mod tests {
     }
 
     #[test]
+    fn test_sint128() {
+        let test_input = &[
+            // SInt128(0)
+            0b0001_0100, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                         0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // SInt128(18446744073709551615)
+            0b0001_0100, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                         0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            // SInt128(-1)
+            0b0001_0100, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+                         0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        ];
+        let expected_output = vec![
+            0,
+            18446744073709551615,
+            -1,
+        ];
+        let count = expected_output.len();
+        assert_eq!(
+            many_m_n(count, count, sint128)(test_input),
+            Ok((
+                &test_input[test_input.len() .. ],
+                expected_output,
+            ))
+        );
+    }
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
     fn test_float32() {
         let test_input = &[
             // Float32(0)
@@ -651,7 +754,7 @@ mod tests {
         let test_input = &[
             0b0101_0001, 0x80,
         ];
-        assert_eq!(ascii_string(test_input).is_err(), true);
+        assert!(ascii_string(test_input).is_err());
     }
 
     #[test]
@@ -732,6 +835,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_latin1_string() {
+        let test_input = &[
+            // AsciiString(length = 4, encoded) with a Latin-1 byte.
+            0b0101_0100, 0x63, 0x61, 0x66, 0xE9,
+            // AsciiString(length = 2, encoded)
+            0b0101_0010, 0x6F, 0x6B,
+        ];
+        assert!(ascii_string(test_input).is_err());
+        assert_eq!(
+            many_m_n(2, 2, latin1_string)(test_input),
+            Ok((
+                &test_input[test_input.len() .. ],
+                vec![String::from("café"), String::from("ok")],
+            ))
+        );
+    }
+
+    #[test]
+    fn test_array_reference_size_3() {
+        let test_input = &[
+            // Array(reference_size = 3, length = 2, encoded)
+            0b1010_0010, 0x00, 0x00, 0x05, 0x01, 0x02, 0x03,
+            // Dictionary(reference_size = 3, length = 1, encoded)
+            0b1101_0001, 0x00, 0xFF, 0xFF, 0x01, 0x00, 0x00,
+        ];
+        assert_eq!(
+            tuple((array(3), dictionary(3)))(test_input),
+            Ok((
+                &test_input[test_input.len() .. ],
+                (vec![5, 66051], vec![(65535, 65536)]),
+            ))
+        );
+    }
+
     #[test]
     fn test_dictionary() {
         let test_input = &[
@@ -759,4 +897,31 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_payload_count_is_unsigned() {
+        // An 8-byte count with the high bit set is not interpreted as negative.
+        let test_input = [0b0001_0011, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE];
+        assert_eq!(
+            payload_count(0b0000_1111)(&test_input),
+            Ok((&test_input[test_input.len() .. ], 0xFFFF_FFFF_FFFF_FFFE))
+        );
+
+        // Data(length = u64::MAX - 1) cannot be satisfied by the input.
+        let mut test_input = test_input.to_vec();
+        test_input.insert(0, 0b0100_1111);
+        assert!(data(&test_input).is_err());
+    }
+
+    #[test]
+    fn test_array_length_exceeds_input() {
+        // Array(reference_size = 1, length = 1e9, trailing: sint64) in a 40-byte input.
+        let mut test_input = vec![
+            0b1010_1111, 0b0001_0011, 0x00, 0x00, 0x00, 0x00, 0x3B, 0x9A, 0xCA, 0x00,
+        ];
+        test_input.resize(40, 0x00);
+        assert!(array(1)(&test_input).is_err());
+        assert!(dictionary(1)(&test_input).is_err());
+    }
 }
@@ -8,9 +8,12 @@
 //
 
 use nom::{
+    Err,
     IResult,
     combinator::map_res,
     bytes::complete::take,
+    error::ErrorKind,
+    multi::many_m_n,
 };
 
 use std::convert::TryFrom;
@@ -25,7 +28,7 @@ use std::convert::TryFrom;
 pub fn be_u64_n(
     n: usize
 ) -> impl Fn(&[u8]) -> IResult<&[u8], u64> {
-    assert!(n >= 1 && n <= 8, "number must be between 1 and 8 bytes, inclusive");
+    assert!((1..=8).contains(&n), "number must be between 1 and 8 bytes, inclusive");
     move |input: &[u8]| {
         let (input, bytes) = take(n)(input)?;
         let value = bytes.iter().fold(0u64, |acc, x| {
@@ -49,14 +52,41 @@ pub fn be_usize_n(
     move |input: &[u8]| {
         map_res(
             be_u64_n(n),
-            |value| usize::try_from(value)
+            usize::try_from
         )(input)
     }
 }
 
+/// Returns a parser which applies the element parser exactly `count` times, where each
+/// element occupies at least `element_size` bytes of the input.
+///
+/// # Notes
+///
+/// 1. The count is checked against the remaining input before any elements are parsed.
+///    This prevents a tiny document from requesting an enormous allocation by encoding
+///    a large collection length.
+pub fn bounded_many<'a, O, F>(
+    count: usize,
+    element_size: usize,
+    f: F,
+) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], Vec<O>>
+where
+    F: Fn(&'a [u8]) -> IResult<&'a [u8], O>,
+{
+    let parser = many_m_n(count, count, f);
+    move |input: &'a [u8]| {
+        let fits = count.checked_mul(element_size)
+            .map_or(false, |length| length <= input.len());
+        if !fits {
+            return Err(Err::Error((input, ErrorKind::ManyMN)));
+        }
+        parser(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{be_u64_n, be_usize_n};
+    use super::{be_u64_n, be_usize_n, bounded_many};
 
     #[test]
     fn test_be_usize_n_length_1() {
@@ -78,4 +108,17 @@ mod tests {
         assert_eq!(input.len(), 0);
         assert_eq!(result, 72057594037928447);
     }
+
+    #[test]
+    fn test_bounded_many_exact() {
+        let (input, result) = bounded_many(2, 2, be_usize_n(2))(&[0x00, 0x01, 0x00, 0x02]).unwrap();
+        assert_eq!(input.len(), 0);
+        assert_eq!(result, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_bounded_many_exceeds_input() {
+        assert!(bounded_many(3, 2, be_usize_n(2))(&[0x00, 0x01, 0x00, 0x02]).is_err());
+        assert!(bounded_many(usize::MAX, 2, be_usize_n(2))(&[0x00, 0x01]).is_err());
+    }
 }
@@ -24,6 +24,8 @@
 //! 1. https://github.com/opensource-apple/CF/blob/master/ForFoundationOnly.h
 //! 2. https://opensource.apple.com/source/CF/CF-855.17/CFBinaryPList.c
 
+use std::fmt;
+
 /// The number of bytes of data required to define a bplist header.
 pub const HEADER_SIZE: usize = 8;
 
@@ -60,6 +62,8 @@ pub type OffsetTable = Vec<usize>;
 /// 1. All values are stored in network byte order (big endian).
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
 pub enum ObjectFormat {
+    /// The null singleton, representing the absence of a value.
+    Null,
     /// A 1-bit boolean literal value.
     Boolean,
     /// A 'fill' byte, used for padding.
@@ -72,9 +76,15 @@ pub enum ObjectFormat {
     UInt32,
     /// A signed 64-bit integer.
     SInt64,
+    /// A signed 128-bit integer, written by CoreFoundation for unsigned 64-bit values
+    /// which do not fit in a signed 64-bit integer.
+    SInt128,
     /// A single-precision 32-bit floating point value.
     Float32,
     /// A double-precision 64-bit floating point value.
+    ///
+    /// Half-precision reals, with a marker of `0b0010_0001`, are not part of the format
+    /// and are rejected as an invalid object format rather than decoded.
     Float64,
     /// A 64-bit double-precision CFAbsoluteTime value.
     Date,
@@ -92,6 +102,31 @@ pub enum ObjectFormat {
     Dictionary,
 }
 
+impl fmt::Display for ObjectFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        use ObjectFormat::*;
+        formatter.write_str(match self {
+            Null => "null",
+            Boolean => "boolean",
+            Fill => "fill",
+            UInt8 => "uint8",
+            UInt16 => "uint16",
+            UInt32 => "uint32",
+            SInt64 => "sint64",
+            SInt128 => "sint128",
+            Float32 => "float32",
+            Float64 => "float64",
+            Date => "date",
+            Data => "data",
+            AsciiString => "ascii string",
+            Utf16String => "utf-16 string",
+            Uid => "uid",
+            Array => "array",
+            Dictionary => "dictionary",
+        })
+    }
+}
+
 impl ObjectFormat {
 
     /// Compute the bitwise AND of the marker byte and tag mask to obtain the its bits.
@@ -100,7 +135,7 @@ impl ObjectFormat {
         match self {
             Boolean =>
                 0b1111_1110,
-            Fill | UInt8 | UInt16 | UInt32 | SInt64 | Float32 | Float64 | Date =>
+            Null | Fill | UInt8 | UInt16 | UInt32 | SInt64 | SInt128 | Float32 | Float64 | Date =>
                 0b1111_1111,
             Data | AsciiString | Utf16String | Uid | Array | Dictionary =>
                 0b1111_0000,
@@ -113,7 +148,7 @@ impl ObjectFormat {
         match self {
             Boolean =>
                 0b0000_0001,
-            Fill | UInt8 | UInt16 | UInt32 | SInt64 | Float32 | Float64 | Date =>
+            Null | Fill | UInt8 | UInt16 | UInt32 | SInt64 | SInt128 | Float32 | Float64 | Date =>
                 0b0000_0000,
             Data | AsciiString | Utf16String | Uid | Array | Dictionary =>
                 0b0000_1111,
@@ -124,6 +159,8 @@ impl ObjectFormat {
     pub fn tag_bits(self) -> u8 {
         use ObjectFormat::*;
         match self {
+            Null =>
+                0b0000_0000,
             Boolean =>
                 0b0000_1000,
             Fill =>
@@ -136,6 +173,8 @@ impl ObjectFormat {
                 0b0001_0010,
             SInt64 => 
                 0b0001_0011,
+            SInt128 =>
+                0b0001_0100,
             Float32 =>
                 0b0010_0010,
             Float64 => 
@@ -184,3 +223,60 @@ pub struct Trailer {
     /// Offset into the file denoting the start of the offset table.
     pub offset_table_offset: usize,
 }
+
+/// The metadata of a binary property list document.
+///
+/// This consists of everything needed to locate the objects within the document.
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct Inspection {
+    /// The document header.
+    pub header: Header,
+    /// The document trailer.
+    pub trailer: Trailer,
+    /// The offset of each object from the start of the document, indexed by object.
+    pub offset_table: OffsetTable,
+}
+
+/// A summary of the objects in a bplist document, relative to its root object.
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub struct DocumentReport {
+    /// The number of objects in the object table.
+    pub number_of_objects: usize,
+    /// The indices of objects which are not reachable from the root object, in ascending order.
+    pub unreachable_objects: Vec<usize>,
+}
+
+/// A non-fatal anomaly in a bplist document.
+///
+/// Anomalies do not prevent a document from being decoded, but indicate that it was
+/// not written by CoreFoundation or has been edited in place.
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub enum Warning {
+    /// Object references are wider than needed to reference every object.
+    OversizedObjectReferences {
+        /// The width in bytes of each object reference.
+        size: usize,
+        /// The smallest width in bytes which can reference every object.
+        minimum_size: usize,
+    },
+    /// An integer object is encoded in more bytes than its value requires.
+    NonMinimalInteger {
+        /// The index of the integer object.
+        object: usize,
+    },
+    /// An object is not reachable from the root object.
+    UnreachableObject {
+        /// The index of the unreachable object.
+        object: usize,
+    },
+}
+
+/// Statistics gathered while deserializing a bplist document.
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone, Hash)]
+pub struct DecodeStats {
+    /// The number of references followed to objects which had already been referenced.
+    ///
+    /// Encoders may share a single object between several collections, most often for
+    /// repeated strings. Each reference after the first to such an object is counted.
+    pub shared_object_references: usize,
+}
@@ -7,7 +7,6 @@
 // copied, modified, or distributed except according to those terms.
 //
 
-use std;
 use std::fmt::{self, Display};
 
 use serde::{de, ser};
@@ -23,10 +22,14 @@ pub enum Error {
     MissingOrInvalidHeader,
     /// The offset table used determine the location of objects in the object table is missing or invalid.
     MissingOrInvalidOffsetTable,
+    /// The object table is empty, although the trailer declares objects within it.
+    MissingOrInvalidObjectTable,
     /// The trailer with the metadata necessary to interpret the offset table and object table is missing or invalid.
     MissingOrInvalidTrailer,
     /// The version number in the header is not one of the versions supported by this library.
     UnsupportedVersion,
+    /// The sort version in the trailer is not zero, and strict sort version checking is enabled.
+    UnsupportedSortVersion,
     /// Encountered a reference to an object not in the offset table.
     InvalidObjectReference,
     /// Encountered an offset to an object not in the object table.
@@ -39,6 +42,8 @@ pub enum Error {
     RootObjectNotArrayOrDictionary,
     /// The current object was expected to be a valid boolean, but parsing it failed.
     ExpectedBool,
+    /// The current object was expected to be a valid null object, but parsing it failed.
+    ExpectedNull,
     /// The current object was expected to be a valid fill byte, but parsing it failed.
     ExpectedFill,
     /// The current object was expected to be a valid 8-bit unsigned integer, but parsing it failed.
@@ -49,6 +54,8 @@ pub enum Error {
     ExpectedUInt32,
     /// The current object was expected to be a valid 64-bit unsigned integer, but parsing it failed.
     ExpectedSInt64,
+    /// The current object was expected to be a valid 128-bit signed integer, but parsing it failed.
+    ExpectedSInt128,
     /// The current object was expected to be a valid 32-bit single-precision floating point value.
     ExpectedFloat32,
     /// The current object was expected to be a valid 64-bit double-precision floating point value.
@@ -67,12 +74,28 @@ pub enum Error {
     ExpectedUid,
     /// The current object was expected to be a valid dictionary, but parsing it failed.
     ExpectedDictionary,
+    /// A dictionary has fewer key and value references than the number of entries it declares.
+    TruncatedDictionary,
     /// Binary property lists are directed acyclic graphs and objects cannot reference each other.
     CycleDetected,
+    /// Collections are nested more deeply than the recursion limit of the deserializer.
+    RecursionLimitExceeded,
     /// The parser limits the maximum nesting level of collections.
+    ///
+    /// No longer returned, as the limit is reported as `RecursionLimitExceeded`.
+    #[deprecated(note = "the nesting limit is reported as `Error::RecursionLimitExceeded`")]
     MaximumDepthExceeded,
     /// Prematurely reached the end of the file.
     Eof,
+    /// Reading or writing the document failed with the contained I/O error message.
+    Io(String),
+    /// The contained error was encountered at the given byte offset into the document.
+    At {
+        /// The offset from the start of the document.
+        offset: usize,
+        /// The error encountered at the offset.
+        source: Box<Error>,
+    },
 }
 
 impl ser::Error for Error {
@@ -87,6 +110,12 @@ impl de::Error for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error.to_string())
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -96,10 +125,14 @@ impl Display for Error {
                 formatter.write_str("missing or invalid bplist header"),
             Error::MissingOrInvalidOffsetTable =>
                 formatter.write_str("missing or invalid bplist offset table"),
+            Error::MissingOrInvalidObjectTable =>
+                formatter.write_str("missing or invalid bplist object table"),
             Error::MissingOrInvalidTrailer =>
                 formatter.write_str("missing or invalid bplist trailer"),
             Error::UnsupportedVersion =>
                 formatter.write_str("document is not a version (0,0) bplist"),
+            Error::UnsupportedSortVersion =>
+                formatter.write_str("document uses an unsupported key sort version"),
             Error::InvalidObjectReference =>
                 formatter.write_str("invalid reference to object not in offset table"),
             Error::InvalidOffsetToObject =>
@@ -112,6 +145,8 @@ impl Display for Error {
                 formatter.write_str("root object is not an array or dictionary"),
             Error::ExpectedBool =>
                 formatter.write_str("expected boolean"),
+            Error::ExpectedNull =>
+                formatter.write_str("expected null"),
             Error::ExpectedFill =>
                 formatter.write_str("expected fill unit type"),
             Error::ExpectedUInt8 =>
@@ -122,6 +157,8 @@ impl Display for Error {
                 formatter.write_str("expected 32-bit unsigned integer"),
             Error::ExpectedSInt64 =>
                 formatter.write_str("expected 64-bit signed integer"),
+            Error::ExpectedSInt128 =>
+                formatter.write_str("expected 128-bit signed integer"),
             Error::ExpectedFloat32 =>
                 formatter.write_str("expected 32-bit single-precision floating point value"),
             Error::ExpectedFloat64 =>
@@ -140,14 +177,30 @@ impl Display for Error {
                 formatter.write_str("expected UID value"),
             Error::ExpectedDictionary =>
                 formatter.write_str("expected dictionary"),
+            Error::TruncatedDictionary =>
+                formatter.write_str("dictionary references are truncated"),
             Error::CycleDetected =>
                 formatter.write_str("cycle detected"),
+            Error::RecursionLimitExceeded =>
+                formatter.write_str("collection nesting depth limit exceeded"),
+            #[allow(deprecated)]
             Error::MaximumDepthExceeded =>
                 formatter.write_str("collection nesting depth limit exceeded"),
             Error::Eof =>
                 formatter.write_str("unexpected end of input"),
+            Error::Io(msg) =>
+                write!(formatter, "i/o error: {}", msg),
+            Error::At { offset, source } =>
+                write!(formatter, "{} at offset {:#x}", source, offset),
         }
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::At { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
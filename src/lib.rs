@@ -7,12 +7,21 @@
 // copied, modified, or distributed except according to those terms.
 //
 
+mod base64;
 mod de;
 mod document;
 mod error;
+mod pointer;
+mod ser;
 
 pub mod object;
-pub use object::Object;
+pub use object::{from_object, to_xml, Date, Object, ObjectRef, Uid};
 
-pub use de::{from_slice, Deserializer};
+pub use de::{
+    from_reader, from_slice, from_slice_with_report, from_slice_with_stats,
+    from_slice_with_warnings, inspect, is_keyed_archive, read_header, read_trailer, to_object,
+    validate, Archive, Deserializer, KeyedArchiveInfo,
+};
+pub use document::{DecodeStats, DocumentReport, Header, Inspection, Trailer, Warning};
 pub use error::{Error, Result};
+pub use ser::{canonicalize_bytes, to_bytes, to_writer, EncodeOptions, ObjectOrder};
@@ -7,14 +7,19 @@
 // copied, modified, or distributed except according to those terms.
 //
 
-use serde::de;
+use serde::de::{self, Deserialize, IntoDeserializer};
+use serde::de::value::{MapDeserializer, SeqDeserializer};
 use ordered_float::OrderedFloat;
 
+use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+use std::convert::TryFrom;
 use std::fmt;
 
-use crate::de::{date, uid};
-use crate::object::Object;
+use crate::de::{date, uid, DateMap, UidMap, PRIVATE_FIELD_TOKEN};
+use crate::error::{self, Error};
+use crate::object::{Object, ObjectRef};
 
 /// Deserialization of bplist objects into an object model which supports
 /// all values that can be losslessly read from and written into a bplist document.
@@ -27,109 +32,596 @@ impl<'de> de::Deserialize<'de> for Object {
     where
         D: de::Deserializer<'de>,
     {
-        struct ObjectVisitor;
+        deserializer.deserialize_any(ObjectVisitor)
+    }
+}
 
-        impl<'de> de::Visitor<'de> for ObjectVisitor {
-            type Value = Object;
+struct ObjectVisitor;
 
-            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("any valid bplist object value")
-            }
+impl<'de> de::Visitor<'de> for ObjectVisitor {
+    type Value = Object;
 
-            #[inline]
-            fn visit_bool<E>(self, value: bool) -> Result<Object, E> {
-                Ok(Object::Boolean(value))
-            }
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid bplist object value")
+    }
+
+    #[inline]
+    fn visit_unit<E>(self) -> Result<Object, E> {
+        Ok(Object::Null)
+    }
+
+    #[inline]
+    fn visit_bool<E>(self, value: bool) -> Result<Object, E> {
+        Ok(Object::Boolean(value))
+    }
+
+    #[inline]
+    fn visit_i64<E>(self, value: i64) -> Result<Object, E> {
+        Ok(Object::Integer(value))
+    }
+
+    #[inline]
+    fn visit_u64<E>(self, value: u64) -> Result<Object, E> 
+    where
+        E: de::Error {
+        if value <= i64::MAX as u64 {
+            Ok(Object::Integer(value as i64))
+        } else {
+            Err(de::Error::custom("u64 value was too large"))
+        }
+    }
+
+    #[inline]
+    fn visit_i128<E>(self, value: i128) -> Result<Object, E>
+    where
+        E: de::Error {
+        i64::try_from(value)
+            .map(Object::Integer)
+            .map_err(|_| de::Error::custom("i128 value was out of range"))
+    }
+
+    #[inline]
+    fn visit_f64<E>(self, value: f64) -> Result<Object, E> {
+        Ok(Object::Real(OrderedFloat::from(value)))
+    }
 
-            #[inline]
-            fn visit_i64<E>(self, value: i64) -> Result<Object, E> {
-                Ok(Object::Integer(value))
+    #[inline]
+    fn visit_str<E>(self, value: &str) -> Result<Object, E> {
+        Ok(Object::String(value.into()))
+    }
+
+    #[inline]
+    fn visit_string<E>(self, value: String) -> Result<Object, E> {
+        Ok(Object::String(value))
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Object, E> {
+        Ok(Object::Data(value.into()))
+    }
+
+    #[inline]
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Object, E> {
+        Ok(Object::Data(value))
+    }
+
+    #[inline]
+    fn visit_seq<V>(self, mut visitor: V) -> Result<Object, V::Error>
+    where
+        V: de::SeqAccess<'de>,
+    {
+        let mut vec = Vec::new();
+        while let Some(elem) = visitor.next_element()? {
+            vec.push(elem);
+        }
+        Ok(Object::Array(vec))
+    }
+
+    fn visit_map<V>(self, mut visitor: V) -> Result<Object, V::Error>
+    where
+        V: de::MapAccess<'de>,
+    {
+        let mut map = BTreeMap::<Object, Object>::new();
+
+        // Re-mapped types without Serde are identified by a special field, which is
+        // distinguished from a dictionary key with the same value by the deserializer.
+        let mut key = match visitor.next_key_seed(MapKeySeed(ObjectVisitor))? {
+            Some(MapKey::PrivateField(ref x)) if x == date::STRUCT_FIELD => {
+                let date_value: date::DateFromF64 = visitor.next_value()?;
+                return Ok(Object::Date(date_value.value));
             }
+            Some(MapKey::PrivateField(ref x)) if x == uid::STRUCT_FIELD => {
+                let uid_value: uid::UidFromU64 = visitor.next_value()?;
+                return Ok(Object::Uid(uid_value.value));
+            }
+            Some(MapKey::PrivateField(x)) => Some(Object::String(x)),
+            Some(MapKey::Object(x)) => Some(x),
+            None => None,
+        };
 
-            #[inline]
-            fn visit_u64<E>(self, value: u64) -> Result<Object, E> 
-            where
-                E: de::Error {
-                if value <= i64::max_value() as u64 {
-                    Ok(Object::Integer(value as i64))
-                } else {
-                    Err(de::Error::custom("u64 value was too large"))
+        // Process all key-value pairs checking for duplicates.
+        while let Some(k) = key {
+            match map.entry(k) {
+                Entry::Occupied(entry) => {
+                    let msg = format!("duplicate key: `{:?}`", entry.key());
+                    return Err(de::Error::custom(msg));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(visitor.next_value()?);
+                    key = visitor.next_key()?;
                 }
             }
+        }
 
-            #[inline]
-            fn visit_f64<E>(self, value: f64) -> Result<Object, E> {
-                Ok(Object::Real(OrderedFloat::from(value)))
-            }
+        Ok(Object::Dictionary(map))
+    }
+}
 
-            #[inline]
-            fn visit_str<E>(self, value: &str) -> Result<Object, E> {
-                Ok(Object::String(value.into()))
-            }
+/// The first key of a map, which may be the field of a pseudo-structure.
+enum MapKey<T> {
+    /// The field of a Date-type or UID-type pseudo-structure.
+    PrivateField(String),
+    /// Any other key, as produced by the wrapped visitor.
+    Object(T),
+}
 
-            #[inline]
-            fn visit_string<E>(self, value: String) -> Result<Object, E> {
-                Ok(Object::String(value))
-            }
+/// Deserializes the first key of a map, probing for a pseudo-structure field.
+/// Any other key is deserialized by the wrapped visitor.
+struct MapKeySeed<V>(V);
 
-            #[inline]
-            fn visit_bytes<E>(self, value: &[u8]) -> Result<Object, E> {
-                Ok(Object::Data(value.into()))
-            }
+impl<'de, V> de::DeserializeSeed<'de> for MapKeySeed<V>
+where
+    V: de::Visitor<'de>,
+{
+    type Value = MapKey<V::Value>;
 
-            #[inline]
-            fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Object, E> {
-                Ok(Object::Data(value))
-            }
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(PRIVATE_FIELD_TOKEN, MapKeyVisitor(self.0))
+    }
+}
 
-            #[inline]
-            fn visit_seq<V>(self, mut visitor: V) -> Result<Object, V::Error>
-            where
-                V: de::SeqAccess<'de>,
-            {
-                let mut vec = Vec::new();
-                while let Some(elem) = visitor.next_element()? {
-                    vec.push(elem);
-                }
-                Ok(Object::Array(vec))
+/// Visits a map key. The bplist deserializer presents a pseudo-structure field as an
+/// enum variant in response to the probe. All other values are forwarded to the
+/// wrapped visitor.
+struct MapKeyVisitor<V>(V);
+
+impl<'de, V> de::Visitor<'de> for MapKeyVisitor<V>
+where
+    V: de::Visitor<'de>,
+{
+    type Value = MapKey<V::Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::EnumAccess<'de>,
+    {
+        let (field, variant) = data.variant::<String>()?;
+        de::VariantAccess::unit_variant(variant)?;
+        Ok(MapKey::PrivateField(field))
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self.0).map(MapKey::Object)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error {
+        self.0.visit_unit().map(MapKey::Object)
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error {
+        self.0.visit_bool(value).map(MapKey::Object)
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error {
+        self.0.visit_i64(value).map(MapKey::Object)
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error {
+        self.0.visit_u64(value).map(MapKey::Object)
+    }
+
+    fn visit_i128<E>(self, value: i128) -> Result<Self::Value, E>
+    where
+        E: de::Error {
+        self.0.visit_i128(value).map(MapKey::Object)
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error {
+        self.0.visit_f64(value).map(MapKey::Object)
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error {
+        self.0.visit_str(value).map(MapKey::Object)
+    }
+
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error {
+        self.0.visit_borrowed_str(value).map(MapKey::Object)
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+    where
+        E: de::Error {
+        self.0.visit_string(value).map(MapKey::Object)
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error {
+        self.0.visit_bytes(value).map(MapKey::Object)
+    }
+
+    fn visit_borrowed_bytes<E>(self, value: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error {
+        self.0.visit_borrowed_bytes(value).map(MapKey::Object)
+    }
+
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error {
+        self.0.visit_byte_buf(value).map(MapKey::Object)
+    }
+
+    fn visit_seq<A>(self, visitor: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        self.0.visit_seq(visitor).map(MapKey::Object)
+    }
+
+    fn visit_map<A>(self, visitor: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        self.0.visit_map(visitor).map(MapKey::Object)
+    }
+}
+
+/// Deserialization of bplist objects into the borrowing object model.
+/// ASCII strings and data are borrowed from the input where the deserializer allows.
+impl<'de> de::Deserialize<'de> for ObjectRef<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<ObjectRef<'de>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ObjectRefVisitor)
+    }
+}
+
+struct ObjectRefVisitor;
+
+impl<'de> de::Visitor<'de> for ObjectRefVisitor {
+    type Value = ObjectRef<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid bplist object value")
+    }
+
+    #[inline]
+    fn visit_unit<E>(self) -> Result<ObjectRef<'de>, E> {
+        Ok(ObjectRef::Null)
+    }
+
+    #[inline]
+    fn visit_bool<E>(self, value: bool) -> Result<ObjectRef<'de>, E> {
+        Ok(ObjectRef::Boolean(value))
+    }
+
+    #[inline]
+    fn visit_i64<E>(self, value: i64) -> Result<ObjectRef<'de>, E> {
+        Ok(ObjectRef::Integer(value))
+    }
+
+    #[inline]
+    fn visit_u64<E>(self, value: u64) -> Result<ObjectRef<'de>, E> 
+    where
+        E: de::Error {
+        if value <= i64::MAX as u64 {
+            Ok(ObjectRef::Integer(value as i64))
+        } else {
+            Err(de::Error::custom("u64 value was too large"))
+        }
+    }
+
+    #[inline]
+    fn visit_i128<E>(self, value: i128) -> Result<ObjectRef<'de>, E>
+    where
+        E: de::Error {
+        i64::try_from(value)
+            .map(ObjectRef::Integer)
+            .map_err(|_| de::Error::custom("i128 value was out of range"))
+    }
+
+    #[inline]
+    fn visit_f64<E>(self, value: f64) -> Result<ObjectRef<'de>, E> {
+        Ok(ObjectRef::Real(OrderedFloat::from(value)))
+    }
+
+    #[inline]
+    fn visit_str<E>(self, value: &str) -> Result<ObjectRef<'de>, E> {
+        Ok(ObjectRef::String(Cow::Owned(value.into())))
+    }
+
+    #[inline]
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<ObjectRef<'de>, E> {
+        Ok(ObjectRef::String(Cow::Borrowed(value)))
+    }
+
+    #[inline]
+    fn visit_string<E>(self, value: String) -> Result<ObjectRef<'de>, E> {
+        Ok(ObjectRef::String(Cow::Owned(value)))
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<ObjectRef<'de>, E> {
+        Ok(ObjectRef::Data(Cow::Owned(value.into())))
+    }
+
+    #[inline]
+    fn visit_borrowed_bytes<E>(self, value: &'de [u8]) -> Result<ObjectRef<'de>, E> {
+        Ok(ObjectRef::Data(Cow::Borrowed(value)))
+    }
+
+    #[inline]
+    fn visit_byte_buf<E>(self, value: Vec<u8>) -> Result<ObjectRef<'de>, E> {
+        Ok(ObjectRef::Data(Cow::Owned(value)))
+    }
+
+    #[inline]
+    fn visit_seq<V>(self, mut visitor: V) -> Result<ObjectRef<'de>, V::Error>
+    where
+        V: de::SeqAccess<'de>,
+    {
+        let mut vec = Vec::new();
+        while let Some(elem) = visitor.next_element()? {
+            vec.push(elem);
+        }
+        Ok(ObjectRef::Array(vec))
+    }
+
+    fn visit_map<V>(self, mut visitor: V) -> Result<ObjectRef<'de>, V::Error>
+    where
+        V: de::MapAccess<'de>,
+    {
+        let mut pairs = Vec::new();
+
+        // Re-mapped types without Serde are identified by a special field.
+        let mut key = match visitor.next_key_seed(MapKeySeed(ObjectRefVisitor))? {
+            Some(MapKey::PrivateField(ref x)) if x == date::STRUCT_FIELD => {
+                let date_value: date::DateFromF64 = visitor.next_value()?;
+                return Ok(ObjectRef::Date(date_value.value));
+            }
+            Some(MapKey::PrivateField(ref x)) if x == uid::STRUCT_FIELD => {
+                let uid_value: uid::UidFromU64 = visitor.next_value()?;
+                return Ok(ObjectRef::Uid(uid_value.value));
             }
+            Some(MapKey::PrivateField(x)) => Some(ObjectRef::String(Cow::Owned(x))),
+            Some(MapKey::Object(x)) => Some(x),
+            None => None,
+        };
 
-            fn visit_map<V>(self, mut visitor: V) -> Result<Object, V::Error>
-            where
-                V: de::MapAccess<'de>,
-            {
-                let mut map = BTreeMap::<Object, Object>::new();
-
-                // Re-mapped types without Serde are identified by a special key.
-                let mut key = visitor.next_key()?;
-
-                // These do not map to Dictionaries.
-                if let Some(Object::String(ref x @ _ )) = key {
-                    if x == date::STRUCT_FIELD {
-                        let date_value: date::DateFromF64 = visitor.next_value()?;
-                        return Ok(Object::Date(date_value.value));
-                    } else if x == uid::STRUCT_FIELD {
-                        let uid_value: uid::UidFromU64 = visitor.next_value()?;
-                        return Ok(Object::Uid(uid_value.value));
-                    }
-                }
+        // Key-value pairs are retained in the order in which they are visited.
+        while let Some(k) = key {
+            pairs.push((k, visitor.next_value()?));
+            key = visitor.next_key()?;
+        }
 
-                // Process all key-value pairs checking for duplicates.
-                while let Some(k) = key {
-                    if map.contains_key(&k) {
-                        let msg = format!("duplicate key: `{:?}`", k);
-                        return Err(de::Error::custom(msg));
-                    } else {
-                        let v = visitor.next_value()?;
-                        map.insert(k, v);
-                        key = visitor.next_key()?;
-                    }
-                }
+        Ok(ObjectRef::Dictionary(pairs))
+    }
+}
 
-                Ok(Object::Dictionary(map))
+/// Deserialize an instance of type `T` from a bplist object.
+///
+/// This allows a value which has already been decoded into the object model to be
+/// converted into a Rust structure without re-encoding it as a bplist document.
+///
+/// # Errors
+///
+/// This conversion can fail if the structure of the object does not match the
+/// structure expected by `T`, as described in `bplist::from_slice`.
+pub fn from_object<'a, T>(object: &'a Object) -> error::Result<T>
+where
+    T: Deserialize<'a>,
+{
+    T::deserialize(object)
+}
+
+impl Object {
+    /// Deserializes the receiver into an instance of type `T`.
+    ///
+    /// This is a convenience wrapper around `bplist::from_object` for types which
+    /// do not borrow from the object.
+    pub fn deserialize_into<T>(&self) -> error::Result<T>
+    where
+        T: de::DeserializeOwned,
+    {
+        from_object(self)
+    }
+}
+
+/// Deserialization of Rust values from the bplist object model.
+/// Strings and data are provided to the visitor borrowed from the object.
+/// `Date` and `Uid` objects are presented as the same pseudo-structures
+/// produced when decoding a document.
+impl<'de> de::Deserializer<'de> for &'de Object {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> error::Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            Object::Null =>
+                visitor.visit_unit(),
+            Object::Boolean(value) =>
+                visitor.visit_bool(*value),
+            Object::Integer(value) =>
+                visitor.visit_i64(*value),
+            Object::Real(value) =>
+                visitor.visit_f64(value.into_inner()),
+            Object::Data(value) =>
+                visitor.visit_borrowed_bytes(value),
+            Object::Date(value) =>
+                visitor.visit_map(DateMap::new(value.absolute_time.into_inner())),
+            Object::Uid(value) =>
+                visitor.visit_map(UidMap::new(value.0)),
+            Object::String(value) =>
+                visitor.visit_borrowed_str(value),
+            Object::Array(objects) =>
+                visitor.visit_seq(SeqDeserializer::new(objects.iter())),
+            Object::Dictionary(pairs) =>
+                visitor.visit_map(MapDeserializer::new(pairs.iter())),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> error::Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            Object::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V
+    ) -> error::Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Enums are externally tagged, as when decoding a document.
+        match self {
+            Object::String(value) =>
+                visitor.visit_enum(value.as_str().into_deserializer()),
+            Object::Dictionary(pairs) if pairs.len() == 1 => {
+                let (variant, content) = pairs.iter().next().unwrap();
+                visitor.visit_enum(EnumDeserializer { variant, content })
             }
+            Object::Dictionary(pairs) =>
+                Err(de::Error::invalid_length(pairs.len(), &"a dictionary with a single entry")),
+            _ =>
+                Err(Error::Message(
+                    format!("expected string or dictionary for enum, found {}", self.type_name())
+                )),
         }
+    }
 
-        deserializer.deserialize_any(ObjectVisitor)
+    fn deserialize_u64<V>(self, visitor: V) -> error::Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Only the raw bits of an eight-byte integer read from a document are decoded as
+        // unsigned. A negative integer in the object model is not a valid u64.
+        match self {
+            Object::Integer(value) if *value < 0 =>
+                Err(de::Error::invalid_value(de::Unexpected::Signed(*value), &visitor)),
+            _ =>
+                self.deserialize_any(visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool
+        u8 u16 u32 u128
+        i8 i16 i32 i64 i128
+        f32 f64
+        char str string
+        seq map
+        bytes byte_buf
+        struct
+        unit unit_struct
+        tuple tuple_struct
+        newtype_struct
+        ignored_any
+        identifier
+    }
+}
+
+/// Access object for an enum variant represented by the single entry of a dictionary,
+/// from the name of the variant to its content.
+struct EnumDeserializer<'de> {
+    variant: &'de Object,
+    content: &'de Object,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> error::Result<(V::Value, Self)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(self.variant)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for EnumDeserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> error::Result<()> {
+        Deserialize::deserialize(self.content)
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> error::Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.content)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> error::Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_seq(self.content, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V
+    ) -> error::Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_struct(self.content, "", fields, visitor)
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for &'de Object {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
     }
 }
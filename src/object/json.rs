@@ -0,0 +1,60 @@
+//
+// Copyright 2020 bplist Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use serde_json::{Map, Value};
+
+use crate::base64;
+use crate::object::Object;
+
+/// Converts the object tree into a JSON value.
+///
+/// Objects without a JSON counterpart are converted as follows:
+///
+/// 1. Data is a Base64 string.
+/// 2. Dates are RFC 3339 strings.
+/// 3. UIDs are objects with a single `CF$UID` member, as in the XML format.
+/// 4. Reals which are not finite are null.
+///
+/// String dictionary keys are used as they are. Other keys are prefixed with their
+/// type name and a colon, followed by the key converted to JSON text, such that the
+/// integer key `5` becomes `"integer:5"`.
+impl From<Object> for Value {
+    fn from(object: Object) -> Value {
+        match object {
+            Object::Null => Value::Null,
+            Object::Boolean(value) => Value::Bool(value),
+            Object::Integer(value) => Value::from(value),
+            Object::Real(value) => Value::from(value.into_inner()),
+            Object::Data(data) => Value::String(base64::encode(&data)),
+            Object::Date(date) => Value::String(date.to_string()),
+            Object::Uid(uid) => {
+                let mut map = Map::new();
+                map.insert(String::from("CF$UID"), Value::from(uid.as_u64()));
+                Value::Object(map)
+            }
+            Object::String(value) => Value::String(value),
+            Object::Array(objects) => objects.into_iter().map(Value::from).collect(),
+            Object::Dictionary(map) =>
+                map.into_iter()
+                    .map(|(key, value)| (json_key(key), Value::from(value)))
+                    .collect(),
+        }
+    }
+}
+
+/// Returns the JSON object member name for a dictionary key.
+fn json_key(key: Object) -> String {
+    match key {
+        Object::String(value) => value,
+        key => {
+            let type_name = key.type_name();
+            format!("{}:{}", type_name, Value::from(key))
+        }
+    }
+}
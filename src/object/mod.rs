@@ -11,22 +11,49 @@
 //!
 //! The bplist format version 00 supports the following object kinds:
 //!
-//! 1. Boolean.
-//! 2. Integers, up to 64 bits long.
-//! 3. Real, single- and double-precision.
-//! 4. Data.
-//! 5. Date.
-//! 6. String.
-//! 7. Uid.
-//! 8. Array.
-//! 9. Dictionary.
+//! 1. Null.
+//! 2. Boolean.
+//! 3. Integers, up to 64 bits long.
+//! 4. Real, single- and double-precision.
+//! 5. Data.
+//! 6. Date.
+//! 7. String.
+//! 8. Uid.
+//! 9. Array.
+//! 10. Dictionary.
 //!
 //! # References
 //!
 //! 1. https://github.com/opensource-apple/CF/blob/master/ForFoundationOnly.h
 //! 2. https://opensource.apple.com/source/CF/CF-855.17/CFBinaryPList.c
 
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::Index;
+use std::slice;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::vec;
+
+use crate::document::HEADER_MAGIC_NUMBER;
+use crate::error::{Error, Result};
+use crate::pointer;
+
+/// The number of seconds between the Unix Epoch and the Core Data Epoch.
+const CORE_DATA_EPOCH_UNIX_OFFSET: f64 = 978_307_200.0;
+
 /// A date structure roughly equivalent to an `NSDate`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// // The Core Data Epoch is 1 January 2001, 00:00:00 UTC.
+/// let date = bplist::Date { absolute_time: 0.0.into() };
+/// assert_eq!(date.to_system_time(), Some(UNIX_EPOCH + Duration::from_secs(978_307_200)));
+/// ```
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct Date {
     /// A double-precision 64-bit offset, in seconds, from the Core Data Epoch.
@@ -34,15 +61,207 @@ pub struct Date {
     pub absolute_time: ordered_float::OrderedFloat<f64>,
 }
 
+impl Date {
+    /// Returns the date corresponding to a `SystemTime`.
+    ///
+    /// Times before the Unix epoch are supported. Precision is limited to that of the
+    /// double-precision absolute time, which is finer than a microsecond for dates
+    /// within a few centuries of 2001.
+    pub fn from_system_time(time: SystemTime) -> Date {
+        let unix_time = match time.duration_since(UNIX_EPOCH) {
+            Ok(offset) => offset.as_secs_f64(),
+            Err(error) => -error.duration().as_secs_f64(),
+        };
+        Date {
+            absolute_time: (unix_time - CORE_DATA_EPOCH_UNIX_OFFSET).into(),
+        }
+    }
+
+    /// Formats the date as an RFC 3339 timestamp in UTC, such as `2001-01-01T00:00:00Z`.
+    ///
+    /// The time is rounded to the nearest millisecond, and fractional seconds are
+    /// included only if they are nonzero. This is equivalent to `to_string`.
+    pub fn to_rfc3339(&self) -> String {
+        self.to_string()
+    }
+
+    /// Converts the date into a `SystemTime`.
+    ///
+    /// Returns `None` if the absolute time is not finite or the date cannot be
+    /// represented as a `SystemTime` on the current platform.
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        let unix_time = self.absolute_time.into_inner() + CORE_DATA_EPOCH_UNIX_OFFSET;
+        if unix_time >= 0.0 {
+            let offset = Duration::try_from_secs_f64(unix_time).ok()?;
+            UNIX_EPOCH.checked_add(offset)
+        } else {
+            let offset = Duration::try_from_secs_f64(-unix_time).ok()?;
+            UNIX_EPOCH.checked_sub(offset)
+        }
+    }
+}
+
+/// Formats the date as an RFC 3339 timestamp in UTC.
+///
+/// Dates with an absolute time which is not finite, or which is too far from the
+/// epoch to be represented in milliseconds, are formatted as the absolute time.
+impl fmt::Display for Date {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let unix_time = self.absolute_time.into_inner() + CORE_DATA_EPOCH_UNIX_OFFSET;
+        let unix_milliseconds = (unix_time * 1e3).round();
+        if !unix_milliseconds.is_finite() || unix_milliseconds.abs() >= i64::MAX as f64 {
+            return write!(formatter, "{}", self.absolute_time);
+        }
+
+        let unix_milliseconds = unix_milliseconds as i64;
+        let days = unix_milliseconds.div_euclid(MILLISECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        let time_of_day = unix_milliseconds.rem_euclid(MILLISECONDS_PER_DAY);
+        write!(
+            formatter,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            time_of_day / 3_600_000,
+            time_of_day / 60_000 % 60,
+            time_of_day / 1_000 % 60
+        )?;
+        if time_of_day % 1_000 != 0 {
+            write!(formatter, ".{:03}", time_of_day % 1_000)?;
+        }
+        formatter.write_str("Z")
+    }
+}
+
+/// The number of milliseconds in a day, ignoring leap seconds.
+const MILLISECONDS_PER_DAY: i64 = 86_400_000;
+
+/// Returns the proleptic Gregorian calendar year, month and day of the day which is
+/// the given number of days after 1 January 1970.
+///
+/// This is the `civil_from_days` algorithm described by Howard Hinnant, which counts
+/// days in 400-year eras starting on 1 March.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month as u32, day as u32)
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::Utc>> for Date {
+    fn from(time: chrono::DateTime<chrono::Utc>) -> Date {
+        let unix_time = time.timestamp() as f64 + f64::from(time.timestamp_subsec_nanos()) / 1e9;
+        Date {
+            absolute_time: (unix_time - CORE_DATA_EPOCH_UNIX_OFFSET).into(),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl std::convert::TryFrom<Date> for chrono::DateTime<chrono::Utc> {
+    type Error = Error;
+
+    /// Converts the date into a `DateTime<Utc>`, rounded to the nearest nanosecond.
+    ///
+    /// Fails if the absolute time is not finite or is outside of the range of dates
+    /// representable by `DateTime<Utc>`.
+    fn try_from(date: Date) -> Result<Self> {
+        let unix_time = date.absolute_time.into_inner() + CORE_DATA_EPOCH_UNIX_OFFSET;
+        let out_of_range = || Error::Message(format!(
+            "date with absolute time {} is out of range",
+            date.absolute_time
+        ));
+        if !unix_time.is_finite() || unix_time.abs() >= i64::MAX as f64 {
+            return Err(out_of_range());
+        }
+
+        // Rounding the fraction may carry into the next second.
+        let mut seconds = unix_time.floor() as i64;
+        let mut nanoseconds = ((unix_time - unix_time.floor()) * 1e9).round() as u32;
+        if nanoseconds >= 1_000_000_000 {
+            seconds += 1;
+            nanoseconds -= 1_000_000_000;
+        }
+        chrono::DateTime::from_timestamp(seconds, nanoseconds).ok_or_else(out_of_range)
+    }
+}
+
+/// A `SystemTime` which is deserialized from a bplist date.
+///
+/// Serde has no notion of a point in time, so `SystemTime` cannot be deserialized
+/// from a Date object directly. Using this type for a struct field performs the
+/// conversion implemented by `Date::to_system_time` during deserialization.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct SystemTimeFromDate(pub SystemTime);
+
 /// Represents a bplist uid.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct Uid(pub u64);
 
+impl Uid {
+    /// Returns a UID with the specified value.
+    pub fn from_u64(value: u64) -> Uid {
+        Uid(value)
+    }
+
+    /// Returns the value of the UID.
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the number of bytes in the minimal big-endian encoding of the value.
+    ///
+    /// UID objects hold 1, 2, 4 or 8 bytes, so this is the smallest of those widths
+    /// which can represent the value.
+    pub fn byte_len(&self) -> usize {
+        match self.0 {
+            0 ..= 0xFF => 1,
+            0x100 ..= 0xFFFF => 2,
+            0x1_0000 ..= 0xFFFF_FFFF => 4,
+            _ => 8,
+        }
+    }
+}
+
+/// Formats the UID as its hexadecimal bytes, such as `Uid(0x0a0b0c)`.
+impl fmt::Display for Uid {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Uid({:#x})", self)
+    }
+}
+
+/// Formats the value of the UID as hexadecimal bytes in big-endian order, such as
+/// `0a0b0c`, omitting leading zero bytes. The alternate flag adds a `0x` prefix.
+impl fmt::LowerHex for Uid {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let significant_bytes = std::cmp::max(1, 8 - self.0.leading_zeros() as usize / 8);
+        if formatter.alternate() {
+            formatter.write_str("0x")?;
+        }
+        write!(formatter, "{:01$x}", self.0, significant_bytes * 2)
+    }
+}
+
 /// Represents any valid bplist object.
 ///
 /// See the `bplist::object` module documentation for usage examples.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum Object {
+    /// Represents the absence of a value, like `NSNull`.
+    ///
+    /// Both null objects and fill bytes are decoded as `Null`. It is serialized as a
+    /// fill byte.
+    Null,
+
     /// Represents a bplist boolean, like `NSNumber`.
     Boolean(bool),
 
@@ -84,7 +303,561 @@ pub enum Object {
     /// The dictionary is backed by a `BTreeMap` meaning that objects have a defined order
     /// however that is not necessarily going to be the order in which they are
     /// encountered during parsing and therefore may not round-trip cleanly.
-    Dictionary(std::collections::BTreeMap<Object, Object>),
+    ///
+    /// Keys are compared structurally, so `Integer(5)` and `Real(5.0)` are distinct
+    /// keys even though `NSNumber` considers them equal. Use `Object::get_numeric` to
+    /// look up a value by numeric key regardless of how the key is stored.
+    Dictionary(BTreeMap<Object, Object>),
+}
+
+/// Represents any valid bplist object, borrowing from the input where possible.
+///
+/// This is the counterpart to `Object` for high-performance scanning of documents.
+/// ASCII strings and data are borrowed directly from the input when deserialized
+/// from a bplist document, while UTF-16 strings are necessarily owned.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum ObjectRef<'a> {
+    /// Represents the absence of a value, like `NSNull`.
+    Null,
+
+    /// Represents a bplist boolean, like `NSNumber`.
+    Boolean(bool),
+
+    /// Represents a bplist integral value of up to 64 bits, like `NSNumber`.
+    Integer(i64),
+
+    /// Represents a bplist floating-point value, like `NSNumber`.
+    Real(ordered_float::OrderedFloat<f64>),
+
+    /// Represents a bplist data instance, like `NSData`.
+    Data(Cow<'a, [u8]>),
+
+    /// Represents a bplist date, like `NSDate`.
+    Date(Date),
+
+    /// Represents a bplist UID value.
+    Uid(Uid),
+
+    /// Represents a bplist string, like `NSString`.
+    String(Cow<'a, str>),
+
+    /// Represents a bplist array of objects, like `NSArray<id>`.
+    Array(Vec<ObjectRef<'a>>),
+
+    /// Represents a bplist dictionary, like `NSDictionary<id,id>`.
+    ///
+    /// Unlike `Object::Dictionary`, the key-value pairs are retained in the order
+    /// in which they are encountered during parsing.
+    Dictionary(Vec<(ObjectRef<'a>, ObjectRef<'a>)>),
+}
+
+impl Object {
+    /// Returns the value of a boolean, or `None` for any other variant.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Object::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of an integer, or `None` for any other variant.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Object::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a real, or `None` for any other variant.
+    ///
+    /// Integers are not converted, as they are a distinct variant.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Object::Real(value) => Some(value.into_inner()),
+            _ => None,
+        }
+    }
+
+    /// Returns the contents of a string, or `None` for any other variant.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Object::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the contents of a data object, or `None` for any other variant.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Object::Data(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the UUID stored in big-endian order in a 16-byte data object, or `None`
+    /// for any other object.
+    #[cfg(feature = "uuid")]
+    pub fn as_uuid(&self) -> Option<uuid::Uuid> {
+        self.as_bytes().and_then(|bytes| uuid::Uuid::from_slice(bytes).ok())
+    }
+
+    /// Returns the elements of an array, or `None` for any other variant.
+    pub fn as_array(&self) -> Option<&[Object]> {
+        match self {
+            Object::Array(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the entries of a dictionary, or `None` for any other variant.
+    pub fn as_dictionary(&self) -> Option<&BTreeMap<Object, Object>> {
+        match self {
+            Object::Dictionary(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the kind of object, such as `"integer"` or `"dictionary"`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Object::Null => "null",
+            Object::Boolean(_) => "boolean",
+            Object::Integer(_) => "integer",
+            Object::Real(_) => "real",
+            Object::Data(_) => "data",
+            Object::Date(_) => "date",
+            Object::Uid(_) => "uid",
+            Object::String(_) => "string",
+            Object::Array(_) => "array",
+            Object::Dictionary(_) => "dictionary",
+        }
+    }
+
+    /// Returns an iterator over the elements of an array.
+    ///
+    /// The iterator is empty if the receiver is not an array.
+    pub fn iter(&self) -> slice::Iter<'_, Object> {
+        self.as_array().unwrap_or(&[]).iter()
+    }
+
+    /// Returns the value for a string key of a dictionary.
+    ///
+    /// Returns `None` if the receiver is not a dictionary or contains no such key.
+    pub fn get(&self, key: &str) -> Option<&Object> {
+        match self {
+            Object::Dictionary(map) => map.get(&Object::String(key.to_owned())),
+            _ => None,
+        }
+    }
+
+    /// Clones the object tree, failing if it consists of more than `max_nodes` objects.
+    ///
+    /// Every object in the tree counts as a node, including dictionary keys. This guards
+    /// against accidentally making deep copies of very large trees.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Message` if the tree consists of more than `max_nodes` objects.
+    pub fn clone_bounded(&self, max_nodes: usize) -> Result<Object> {
+        let mut remaining_nodes = max_nodes;
+        self.clone_counting_nodes(&mut remaining_nodes).ok_or_else(|| {
+            Error::Message(format!("object tree exceeds the limit of {} nodes", max_nodes))
+        })
+    }
+
+    /// Compares two objects for equality, treating reals within `epsilon` of each other
+    /// as equal.
+    ///
+    /// Reals are compared exactly by `PartialEq`, so the same nominal value decoded from
+    /// a single-precision and a double-precision encoding will not be equal. This
+    /// comparison recurses through arrays and dictionary values. Dictionary keys must
+    /// match exactly.
+    pub fn approx_eq(&self, other: &Object, epsilon: f64) -> bool {
+        match (self, other) {
+            (Object::Real(lhs), Object::Real(rhs)) =>
+                (lhs == rhs) || (lhs.into_inner() - rhs.into_inner()).abs() <= epsilon,
+            (Object::Array(lhs), Object::Array(rhs)) =>
+                (lhs.len() == rhs.len()) &&
+                lhs.iter().zip(rhs.iter()).all(|(lhs, rhs)| lhs.approx_eq(rhs, epsilon)),
+            (Object::Dictionary(lhs), Object::Dictionary(rhs)) =>
+                (lhs.len() == rhs.len()) &&
+                lhs.iter().all(|(key, lhs)| {
+                    rhs.get(key).map_or(false, |rhs| lhs.approx_eq(rhs, epsilon))
+                }),
+            _ =>
+                self == other,
+        }
+    }
+
+    /// Looks up a value by JSON Pointer.
+    ///
+    /// Each reference token of the pointer selects either the value for a string key
+    /// of a dictionary, or an element of an array by index. The empty pointer refers
+    /// to the receiver. Returns `None` if the pointer is malformed or there is no value
+    /// at the location it identifies.
+    pub fn pointer(&self, pointer: &str) -> Option<&Object> {
+        pointer::tokens(pointer)?
+            .into_iter()
+            .try_fold(self, |object, token| match object {
+                Object::Array(objects) =>
+                    pointer::index(&token).and_then(|index| objects.get(index)),
+                Object::Dictionary(map) =>
+                    map.get(&Object::String(token)),
+                _ =>
+                    None,
+            })
+    }
+
+    /// Returns whether a value exists at the location identified by a JSON Pointer.
+    pub fn path_exists(&self, pointer: &str) -> bool {
+        self.pointer(pointer).is_some()
+    }
+
+    /// Returns the value for a numeric key of a dictionary, matching either an integer
+    /// or a real key which is numerically equal to `n`.
+    ///
+    /// Returns `None` if the receiver is not a dictionary or contains no such key.
+    pub fn get_numeric(&self, n: f64) -> Option<&Object> {
+        // Integer keys are compared as integers, since converting them to floating point
+        // would round distinct integers above 2^53 to the same value. `n` is an integer
+        // key only if it converts exactly; `i64::MAX` is the saturated result of a value
+        // which is out of range, as it has no exact floating point representation.
+        let integer = Some(n as i64).filter(|&integer| integer != i64::MAX && integer as f64 == n);
+        match self {
+            Object::Dictionary(map) =>
+                map.iter()
+                    .find(|(key, _)| match key {
+                        Object::Integer(value) => Some(*value) == integer,
+                        Object::Real(value) => value.into_inner() == n,
+                        _ => false,
+                    })
+                    .map(|(_, value)| value),
+            _ =>
+                None,
+        }
+    }
+
+    /// Replaces data objects which contain a bplist document with the decoded document,
+    /// up to `max_depth` levels of embedding.
+    ///
+    /// Some preferences store nested property lists as data. Data in arrays and
+    /// dictionary values which begins with the bplist magic number and decodes
+    /// successfully is replaced, and the decoded tree is expanded in turn with one
+    /// fewer level remaining. Data which fails to decode is left unchanged, as are
+    /// dictionary keys. A `max_depth` of zero leaves the tree unchanged.
+    pub fn expand_embedded_plists(&mut self, max_depth: usize) {
+        if max_depth == 0 {
+            return;
+        }
+        match self {
+            Object::Data(data) if data.starts_with(HEADER_MAGIC_NUMBER) => {
+                if let Ok(mut object) = crate::from_slice::<Object>(data) {
+                    object.expand_embedded_plists(max_depth - 1);
+                    *self = object;
+                }
+            }
+            Object::Array(objects) =>
+                objects.iter_mut().for_each(|object| object.expand_embedded_plists(max_depth)),
+            Object::Dictionary(map) =>
+                map.values_mut().for_each(|object| object.expand_embedded_plists(max_depth)),
+            _ => {}
+        }
+    }
+
+    /// Applies a transformation to every string in the tree in place.
+    ///
+    /// Strings in arrays, dictionary keys and dictionary values are all transformed.
+    /// If two keys of a dictionary transform to the same string, the value of the key
+    /// which sorts last before transformation is retained.
+    pub fn map_strings<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str) -> String,
+    {
+        self.map_strings_with(&mut f);
+    }
+
+    /// Applies the transformation recursively, borrowing the closure at each level.
+    fn map_strings_with<F>(&mut self, f: &mut F)
+    where
+        F: FnMut(&str) -> String,
+    {
+        match self {
+            Object::String(value) =>
+                *value = f(value),
+            Object::Array(objects) =>
+                objects.iter_mut().for_each(|object| object.map_strings_with(f)),
+            Object::Dictionary(map) => {
+                *map = std::mem::take(map)
+                    .into_iter()
+                    .map(|(mut key, mut value)| {
+                        key.map_strings_with(f);
+                        value.map_strings_with(f);
+                        (key, value)
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+
+    /// Sorts the elements of every array in the tree, including those nested in
+    /// dictionary keys, into ascending order.
+    ///
+    /// This allows trees to be compared regardless of the order of array elements,
+    /// for example when an encoder does not preserve it. Note that this changes the
+    /// meaning of the tree, as the order of array elements is significant in a
+    /// property list.
+    pub fn sort_arrays_recursively(&mut self) {
+        match self {
+            Object::Array(objects) => {
+                objects.iter_mut().for_each(Object::sort_arrays_recursively);
+                objects.sort();
+            }
+            Object::Dictionary(map) => {
+                *map = std::mem::take(map)
+                    .into_iter()
+                    .map(|(mut key, mut value)| {
+                        key.sort_arrays_recursively();
+                        value.sort_arrays_recursively();
+                        (key, value)
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+
+    /// Calls `f` on every object in the tree, depth-first, allowing each to be modified
+    /// or replaced in place.
+    ///
+    /// The contents of an array or dictionary, including its keys, are visited before
+    /// the collection itself, so `f` sees a collection after its contents have been
+    /// transformed. If two keys of a dictionary transform to the same object, the value
+    /// of the key which sorts last before transformation is retained.
+    pub fn visit_mut<F>(&mut self, f: &mut F)
+    where
+        F: FnMut(&mut Object),
+    {
+        match self {
+            Object::Array(objects) =>
+                objects.iter_mut().for_each(|object| object.visit_mut(f)),
+            Object::Dictionary(map) => {
+                *map = std::mem::take(map)
+                    .into_iter()
+                    .map(|(mut key, mut value)| {
+                        key.visit_mut(f);
+                        value.visit_mut(f);
+                        (key, value)
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+        f(self);
+    }
+
+    /// Returns the number of structurally distinct objects in the tree, including the
+    /// receiver itself.
+    ///
+    /// Each array, dictionary, key and value is counted once however many times it
+    /// occurs. This is the number of objects written when the tree is serialized with
+    /// `EncodeOptions::share_collections`, so comparing it with the total number of
+    /// objects in the tree reports how much sharing would save.
+    pub fn count_distinct_subtrees(&self) -> usize {
+        let mut subtrees = HashSet::new();
+        self.collect_subtrees(&mut subtrees);
+        subtrees.len()
+    }
+
+    /// Adds the receiver and every object it contains to the set of subtrees.
+    fn collect_subtrees<'a>(&'a self, subtrees: &mut HashSet<&'a Object>) {
+        // Equal subtrees have equal descendants, which have already been counted.
+        if !subtrees.insert(self) {
+            return;
+        }
+        match self {
+            Object::Array(objects) =>
+                objects.iter().for_each(|object| object.collect_subtrees(subtrees)),
+            Object::Dictionary(map) =>
+                map.iter().for_each(|(key, value)| {
+                    key.collect_subtrees(subtrees);
+                    value.collect_subtrees(subtrees);
+                }),
+            _ => {}
+        }
+    }
+
+    /// Clones the object tree, decrementing the node count for each object cloned.
+    /// Returns `None` if the count is exhausted.
+    fn clone_counting_nodes(&self, remaining_nodes: &mut usize) -> Option<Object> {
+        *remaining_nodes = remaining_nodes.checked_sub(1)?;
+        match self {
+            Object::Array(objects) => {
+                let objects = objects.iter()
+                    .map(|object| object.clone_counting_nodes(remaining_nodes))
+                    .collect::<Option<Vec<Object>>>()?;
+                Some(Object::Array(objects))
+            }
+            Object::Dictionary(pairs) => {
+                let pairs = pairs.iter()
+                    .map(|(key, value)| {
+                        let key = key.clone_counting_nodes(remaining_nodes)?;
+                        let value = value.clone_counting_nodes(remaining_nodes)?;
+                        Some((key, value))
+                    })
+                    .collect::<Option<BTreeMap<Object, Object>>>()?;
+                Some(Object::Dictionary(pairs))
+            }
+            _ =>
+                Some(self.clone()),
+        }
+    }
+
+    /// Returns the name of the variant of the receiver, for use in diagnostics.
+    fn variant_name(&self) -> &'static str {
+        match self {
+            Object::Null => "Object::Null",
+            Object::Boolean(_) => "Object::Boolean",
+            Object::Integer(_) => "Object::Integer",
+            Object::Real(_) => "Object::Real",
+            Object::Data(_) => "Object::Data",
+            Object::Date(_) => "Object::Date",
+            Object::Uid(_) => "Object::Uid",
+            Object::String(_) => "Object::String",
+            Object::Array(_) => "Object::Array",
+            Object::Dictionary(_) => "Object::Dictionary",
+        }
+    }
+}
+
+/// Looks up the value for a string key of a dictionary.
+///
+/// # Panics
+///
+/// Panics if the receiver is not a dictionary or contains no such key. Use
+/// `Object::get` to handle these cases instead.
+impl Index<&str> for Object {
+    type Output = Object;
+
+    fn index(&self, key: &str) -> &Object {
+        match self {
+            Object::Dictionary(_) =>
+                self.get(key).unwrap_or_else(|| panic!("no entry found for key `{}`", key)),
+            _ =>
+                panic!("cannot index {} with key `{}`", self.variant_name(), key),
+        }
+    }
+}
+
+/// Returns the element of an array at an index.
+///
+/// # Panics
+///
+/// Panics if the receiver is not an array or the index is out of bounds.
+impl Index<usize> for Object {
+    type Output = Object;
+
+    fn index(&self, index: usize) -> &Object {
+        match self {
+            Object::Array(objects) =>
+                objects.get(index).unwrap_or_else(|| {
+                    panic!("index {} out of bounds for array of length {}", index, objects.len())
+                }),
+            _ =>
+                panic!("cannot index {} with index {}", self.variant_name(), index),
+        }
+    }
+}
+
+/// Iterates over the elements of an array, or nothing if the object is not an array.
+impl IntoIterator for Object {
+    type Item = Object;
+    type IntoIter = vec::IntoIter<Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            Object::Array(objects) => objects.into_iter(),
+            _ => Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Object {
+    type Item = &'a Object;
+    type IntoIter = slice::Iter<'a, Object>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl From<bool> for Object {
+    fn from(value: bool) -> Self {
+        Object::Boolean(value)
+    }
+}
+
+impl From<i64> for Object {
+    fn from(value: i64) -> Self {
+        Object::Integer(value)
+    }
+}
+
+impl From<f64> for Object {
+    fn from(value: f64) -> Self {
+        Object::Real(value.into())
+    }
+}
+
+impl From<&str> for Object {
+    fn from(value: &str) -> Self {
+        Object::String(value.to_owned())
+    }
+}
+
+impl From<String> for Object {
+    fn from(value: String) -> Self {
+        Object::String(value)
+    }
+}
+
+/// Bytes are converted to a data object, rather than an array of integers.
+impl From<Vec<u8>> for Object {
+    fn from(value: Vec<u8>) -> Self {
+        Object::Data(value)
+    }
+}
+
+/// A UUID is converted to a 16-byte data object, in big-endian order.
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for Object {
+    fn from(value: uuid::Uuid) -> Self {
+        Object::Data(value.as_bytes().to_vec())
+    }
+}
+
+impl From<Vec<Object>> for Object {
+    fn from(value: Vec<Object>) -> Self {
+        Object::Array(value)
+    }
+}
+
+/// Collects key-value pairs into a dictionary. Later values replace earlier values
+/// for equal keys.
+impl FromIterator<(Object, Object)> for Object {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (Object, Object)>,
+    {
+        Object::Dictionary(iter.into_iter().collect())
+    }
 }
 
 mod de;
+#[cfg(feature = "json")]
+mod json;
+mod ser;
+mod xml;
+
+pub use self::de::from_object;
+pub use self::xml::to_xml;
@@ -0,0 +1,85 @@
+//
+// Copyright 2020 bplist Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::de::{date, uid};
+use crate::error;
+use crate::object::{Date, Object, Uid};
+
+impl Object {
+    /// Serializes the receiver into a bplist document.
+    ///
+    /// This is the inverse of `bplist::from_slice::<Object>`, and a convenience wrapper
+    /// around `bplist::to_bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Fails with `Error::RootObjectNotArrayOrDictionary` unless the receiver is an
+    /// array or dictionary.
+    pub fn to_bytes(&self) -> error::Result<Vec<u8>> {
+        crate::to_bytes(self)
+    }
+}
+
+impl Serialize for Object {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Object::Null =>
+                serializer.serialize_unit(),
+            Object::Boolean(value) =>
+                serializer.serialize_bool(*value),
+            Object::Integer(value) =>
+                serializer.serialize_i64(*value),
+            Object::Real(value) =>
+                serializer.serialize_f64(value.into_inner()),
+            Object::Data(value) =>
+                serializer.serialize_bytes(value),
+            Object::Date(value) =>
+                value.serialize(serializer),
+            Object::Uid(value) =>
+                value.serialize(serializer),
+            Object::String(value) =>
+                serializer.serialize_str(value),
+            Object::Array(objects) =>
+                serializer.collect_seq(objects),
+            Object::Dictionary(pairs) =>
+                serializer.collect_map(pairs),
+        }
+    }
+}
+
+/// Dates are serialized as a pseudo-structure, which the bplist serializer encodes
+/// as a Date object.
+impl Serialize for Date {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut structure = serializer.serialize_struct(date::STRUCT_NAME, 1)?;
+        structure.serialize_field(date::STRUCT_FIELD, &self.absolute_time.into_inner())?;
+        structure.end()
+    }
+}
+
+/// UIDs are serialized as a pseudo-structure, which the bplist serializer encodes
+/// as a UID object.
+impl Serialize for Uid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut structure = serializer.serialize_struct(uid::STRUCT_NAME, 1)?;
+        structure.serialize_field(uid::STRUCT_FIELD, &self.0)?;
+        structure.end()
+    }
+}
@@ -0,0 +1,172 @@
+//
+// Copyright 2020 bplist Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+//! # XML property list export.
+//!
+//! # References
+//!
+//! 1. https://www.apple.com/DTDs/PropertyList-1.0.dtd
+
+use crate::base64;
+use crate::object::{Date, Object};
+
+/// The XML declaration and document type which begin every XML property list.
+const PROLOGUE: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+    "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" ",
+    "\"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+    "<plist version=\"1.0\">\n",
+);
+
+/// Converts the object tree into an XML property list document.
+///
+/// The document is formatted as `plutil -convert xml1` formats it, with one element
+/// per line indented by tabs, except that data is written on a single line. The XML
+/// format has no counterpart for some objects, which are written as follows:
+///
+/// 1. Null is an empty string.
+/// 2. UIDs are dictionaries with a single `CF$UID` key, as CoreFoundation writes them.
+/// 3. Dates are truncated to whole seconds.
+/// 4. Dictionary keys which are not strings are prefixed with their type name and a
+///    colon, followed by their text, such that the integer key `5` becomes `integer:5`.
+///
+/// # Examples
+///
+/// ```
+/// use bplist::object::{self, Object};
+///
+/// let object = Object::Array(vec![Object::Boolean(true), Object::Integer(5)]);
+/// assert!(object::to_xml(&object).ends_with(
+///     "<array>\n\t<true/>\n\t<integer>5</integer>\n</array>\n</plist>\n"
+/// ));
+/// ```
+pub fn to_xml(object: &Object) -> String {
+    let mut xml = String::from(PROLOGUE);
+    write_object(&mut xml, object, 0);
+    xml.push_str("</plist>\n");
+    xml
+}
+
+/// Appends the element for the object on its own line at the given depth.
+fn write_object(xml: &mut String, object: &Object, depth: usize) {
+    match object {
+        Object::Null =>
+            write_element(xml, "string", "", depth),
+        Object::Boolean(true) =>
+            write_line(xml, "<true/>", depth),
+        Object::Boolean(false) =>
+            write_line(xml, "<false/>", depth),
+        Object::Array(objects) if objects.is_empty() =>
+            write_line(xml, "<array/>", depth),
+        Object::Array(objects) => {
+            write_line(xml, "<array>", depth);
+            for object in objects {
+                write_object(xml, object, depth + 1);
+            }
+            write_line(xml, "</array>", depth);
+        }
+        Object::Dictionary(map) if map.is_empty() =>
+            write_line(xml, "<dict/>", depth),
+        Object::Dictionary(map) => {
+            write_line(xml, "<dict>", depth);
+            for (key, value) in map {
+                write_element(xml, "key", &key_text(key), depth + 1);
+                write_object(xml, value, depth + 1);
+            }
+            write_line(xml, "</dict>", depth);
+        }
+        Object::Uid(uid) => {
+            write_line(xml, "<dict>", depth);
+            write_element(xml, "key", "CF$UID", depth + 1);
+            write_element(xml, "integer", &uid.as_u64().to_string(), depth + 1);
+            write_line(xml, "</dict>", depth);
+        }
+        scalar =>
+            write_element(xml, element_name(scalar), &scalar_text(scalar), depth),
+    }
+}
+
+/// Returns the name of the element for a scalar object.
+fn element_name(object: &Object) -> &'static str {
+    match object {
+        Object::Integer(_) => "integer",
+        Object::Real(_) => "real",
+        Object::Data(_) => "data",
+        Object::Date(_) => "date",
+        _ => "string",
+    }
+}
+
+/// Returns the text of the element for a scalar object.
+fn scalar_text(object: &Object) -> String {
+    match object {
+        Object::Null => String::new(),
+        Object::Boolean(value) => value.to_string(),
+        Object::Integer(value) => value.to_string(),
+        Object::Real(value) => real_text(value.into_inner()),
+        Object::Data(data) => base64::encode(data),
+        Object::Date(date) => {
+            Date { absolute_time: date.absolute_time.floor().into() }.to_string()
+        }
+        Object::String(value) => value.clone(),
+        Object::Uid(uid) => uid.as_u64().to_string(),
+        Object::Array(_) | Object::Dictionary(_) => format!("{:?}", object),
+    }
+}
+
+/// Returns the text of a real, spelling out values which are not finite as
+/// CoreFoundation does.
+fn real_text(value: f64) -> String {
+    if value.is_nan() {
+        String::from("nan")
+    } else if value.is_infinite() {
+        String::from(if value > 0.0 { "+infinity" } else { "-infinity" })
+    } else {
+        value.to_string()
+    }
+}
+
+/// Returns the text of a dictionary key.
+fn key_text(key: &Object) -> String {
+    match key {
+        Object::String(value) => value.clone(),
+        key => format!("{}:{}", key.type_name(), scalar_text(key)),
+    }
+}
+
+/// Appends an element containing the escaped text on its own line.
+fn write_element(xml: &mut String, name: &str, text: &str, depth: usize) {
+    indent(xml, depth);
+    xml.push('<');
+    xml.push_str(name);
+    xml.push('>');
+    for character in text.chars() {
+        match character {
+            '&' => xml.push_str("&amp;"),
+            '<' => xml.push_str("&lt;"),
+            '>' => xml.push_str("&gt;"),
+            character => xml.push(character),
+        }
+    }
+    xml.push_str("</");
+    xml.push_str(name);
+    xml.push_str(">\n");
+}
+
+/// Appends the markup on its own line.
+fn write_line(xml: &mut String, markup: &str, depth: usize) {
+    indent(xml, depth);
+    xml.push_str(markup);
+    xml.push('\n');
+}
+
+/// Appends the indentation for the given depth.
+fn indent(xml: &mut String, depth: usize) {
+    xml.push_str(&"\t".repeat(depth));
+}
@@ -0,0 +1,47 @@
+//
+// Copyright 2020 bplist Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+//! # JSON Pointers.
+//!
+//! Objects within a document may be addressed using a JSON Pointer, where each
+//! reference token is either a dictionary key or an array index.
+//!
+//! # References
+//!
+//! 1. https://tools.ietf.org/html/rfc6901
+
+/// Splits a JSON Pointer into its unescaped reference tokens.
+///
+/// The empty pointer refers to the root and has no tokens. Returns `None` if a
+/// non-empty pointer does not begin with a `/`.
+pub fn tokens(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        return None;
+    }
+
+    Some(
+        pointer[1 ..]
+            .split('/')
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .collect()
+    )
+}
+
+/// Parses a reference token as an array index.
+///
+/// Leading zeroes are not permitted, other than for the index 0 itself.
+pub fn index(token: &str) -> Option<usize> {
+    if token.starts_with('+') || (token.len() > 1 && token.starts_with('0')) {
+        return None;
+    }
+    token.parse().ok()
+}
@@ -0,0 +1,985 @@
+//
+// Copyright 2020 bplist Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+mod number;
+
+use serde::ser::{self, Serialize};
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryFrom;
+use std::io;
+
+use crate::document::{
+    HEADER_MAGIC_NUMBER,
+    HEADER_SIZE,
+    HEADER_VERSION_00,
+    TRAILER_PREAMBLE_UNUSED_SIZE,
+    ObjectFormat,
+    Trailer,
+};
+use crate::de::{date, uid};
+use crate::error::{Error, Result};
+use crate::object::Uid;
+
+use self::number::NumberSerializer;
+
+/// The order in which the objects of a document are written to the object table.
+///
+/// In either order the root object is written first, and an object referenced more
+/// than once is written at its first occurrence.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default)]
+pub enum ObjectOrder {
+    /// Each collection is followed by the objects it references, each of which is in
+    /// turn followed by the objects it references. This is the order written by
+    /// CoreFoundation and by Python's `plistlib`.
+    #[default]
+    DepthFirst,
+    /// Each collection is followed by the objects it references, and only then by the
+    /// objects which those reference in turn.
+    BreadthFirst,
+}
+
+/// Options which control how a value is encoded as a bplist document.
+///
+/// # Example
+///
+/// ```
+/// use bplist::{EncodeOptions, ObjectOrder};
+///
+/// let options = EncodeOptions::new().object_order(ObjectOrder::BreadthFirst);
+/// let bytes = options.to_bytes(&vec![vec![1, 2], vec![3]]).unwrap();
+/// assert_eq!(bplist::from_slice::<Vec<Vec<u8>>>(&bytes).unwrap(), vec![vec![1, 2], vec![3]]);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct EncodeOptions {
+    /// The order in which objects are written to the object table.
+    object_order: ObjectOrder,
+    /// The width in bytes of object references in collections, if not the smallest
+    /// width which can address every object.
+    object_reference_size: Option<usize>,
+    /// Whether structurally equal collections share a single object.
+    share_collections: bool,
+    /// Whether dictionary entries are written in the order they are serialized,
+    /// rather than ordered by their keys.
+    preserve_entry_order: bool,
+}
+
+impl EncodeOptions {
+
+    /// Returns the default options, which are used by `bplist::to_bytes`.
+    pub fn new() -> Self {
+        EncodeOptions::default()
+    }
+
+    /// Sets the order in which objects are written to the object table.
+    pub fn object_order(mut self, object_order: ObjectOrder) -> Self {
+        self.object_order = object_order;
+        self
+    }
+
+    /// Sets whether structurally equal arrays and dictionaries share a single object.
+    ///
+    /// Identical scalars are always shared. By default each collection is written as
+    /// a separate object, as CoreFoundation does. Sharing collections produces a more
+    /// compact document for values with repeated subtrees, which decodes to the same
+    /// value.
+    pub fn share_collections(mut self, share_collections: bool) -> Self {
+        self.share_collections = share_collections;
+        self
+    }
+
+    /// Sets the width in bytes of object references in collections.
+    pub(crate) fn object_reference_size(mut self, object_reference_size: usize) -> Self {
+        self.object_reference_size = Some(object_reference_size);
+        self
+    }
+
+    /// Sets whether dictionary entries are written in the order they are serialized.
+    pub(crate) fn preserve_entry_order(mut self, preserve_entry_order: bool) -> Self {
+        self.preserve_entry_order = preserve_entry_order;
+        self
+    }
+
+    /// Serialize an instance of type `T` into a bplist document with these options.
+    ///
+    /// # Errors
+    ///
+    /// Serialization can fail for any of the reasons described in `bplist::to_bytes`.
+    pub fn to_bytes<T>(&self, value: &T) -> Result<Vec<u8>>
+    where
+        T: Serialize + ?Sized,
+    {
+        let mut output = Vec::new();
+        self.to_writer(&mut output, value)?;
+        Ok(output)
+    }
+
+    /// Serialize an instance of type `T` as a bplist document into an I/O stream with
+    /// these options.
+    ///
+    /// # Errors
+    ///
+    /// Serialization can fail for any of the reasons described in `bplist::to_writer`.
+    pub fn to_writer<W, T>(&self, writer: &mut W, value: &T) -> Result<()>
+    where
+        W: io::Write,
+        T: Serialize + ?Sized,
+    {
+        let mut serializer = Serializer::new(*self);
+        let root_object = value.serialize(&mut serializer)?;
+        serializer.write_document(writer, root_object)
+    }
+
+}
+
+/// Re-encode a bplist document in canonical form.
+///
+/// The document is decoded into an `Object` tree and serialized again, so integers
+/// are written in their narrowest width, identical scalars are shared, dictionary
+/// entries are sorted and objects are laid out depth-first. Documents which decode
+/// to equal objects therefore canonicalize to identical bytes, which makes the
+/// result suitable for content-addressing. Unreachable objects are discarded.
+///
+/// # Errors
+///
+/// This can fail for any of the reasons described in `from_slice` when decoding
+/// the input as an `Object`.
+pub fn canonicalize_bytes(input: &[u8]) -> Result<Vec<u8>> {
+    to_bytes(&crate::to_object(input)?)
+}
+
+/// An object in the object table, which is encoded once the document is complete.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum Entry {
+    /// A fully-encoded object which does not reference other objects.
+    Scalar(Vec<u8>),
+    /// An array of references to its elements.
+    Array(Vec<usize>),
+    /// A dictionary of references to its keys and values.
+    Dictionary(Vec<(usize, usize)>),
+}
+
+/// The dictionary reserved for an enum variant, and the name of the variant as its key.
+#[derive(Clone, Copy, Debug)]
+struct VariantTag {
+    object: usize,
+    key: usize,
+}
+
+/// A structure that serializes Rust values into a bplist document.
+///
+/// Objects are collected into an object table as they are serialized. Each serialized
+/// value yields the index of its object, which is used as a reference by collections.
+/// Repeated scalars, such as dictionary keys, share a single object, as may repeated
+/// collections. The objects are reordered when the document is written, so that the
+/// root object is first.
+#[derive(Debug)]
+struct Serializer {
+    /// The objects of the document, indexed by object reference.
+    objects: Vec<Entry>,
+    /// The index of each distinct scalar object, keyed by its encoding.
+    scalars: HashMap<Vec<u8>, usize>,
+    /// The index of each distinct collection, if collections are shared.
+    collections: HashMap<Entry, usize>,
+    /// The options controlling how the document is written.
+    options: EncodeOptions,
+}
+
+/// Serialize an instance of type `T` into a bplist document.
+///
+/// Maps are encoded as dictionaries. As in the bplist format itself, their keys may
+/// be any value with a bplist representation, such as integers or data, not only strings.
+/// `None` and unit values are encoded as fill objects, which decode as `None`.
+/// Enums are externally tagged: a unit variant is encoded as its name, and any other
+/// variant as a dictionary with a single entry from its name to its content.
+///
+/// # Example
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct User {
+///     fingerprint: String,
+///     location: String,
+/// }
+///
+/// fn main() {
+///     let user = User {
+///         fingerprint: String::from("0xF9BA143B95FF6D82"),
+///         location: String::from("Menlo Park, CA"),
+///     };
+///
+///     let bytes = bplist::to_bytes(&user).unwrap();
+///     assert_eq!(bplist::from_slice::<User>(&bytes).unwrap(), user);
+/// }
+/// ```
+///
+/// # Errors
+///
+/// The root object of a document must be an array or dictionary, so serialization
+/// fails with `Error::RootObjectNotArrayOrDictionary` if `T` serializes to any other
+/// kind of object. Serialization also fails if `T` contains a value with no bplist
+/// representation, or if `T`'s implementation of `Serialize` decides to fail.
+pub fn to_bytes<T>(value: &T) -> Result<Vec<u8>>
+where
+    T: Serialize + ?Sized,
+{
+    EncodeOptions::new().to_bytes(value)
+}
+
+/// Serialize an instance of type `T` as a bplist document into an I/O stream.
+///
+/// The object table is built in memory, as the offset table and trailer which follow
+/// it describe the location of every object. The document is then written to the
+/// stream in order.
+///
+/// # Errors
+///
+/// Serialization can fail for any of the reasons described in `to_bytes`. Failures
+/// writing to the stream are reported as `Error::Io`.
+pub fn to_writer<W, T>(writer: &mut W, value: &T) -> Result<()>
+where
+    W: io::Write,
+    T: Serialize + ?Sized,
+{
+    EncodeOptions::new().to_writer(writer, value)
+}
+
+impl Serializer {
+
+    /// Returns a new serializer with an empty object table.
+    fn new(options: EncodeOptions) -> Self {
+        Serializer {
+            objects: Vec::new(),
+            scalars: HashMap::new(),
+            collections: HashMap::new(),
+            options,
+        }
+    }
+
+    /// Appends an object to the object table and returns its index.
+    fn push(&mut self, entry: Entry) -> usize {
+        self.objects.push(entry);
+        self.objects.len() - 1
+    }
+
+    /// Appends an encoded scalar object to the object table and returns its index.
+    ///
+    /// Identical scalars are only stored once, with every occurrence referencing the
+    /// same object.
+    fn push_scalar(&mut self, encoded: Vec<u8>) -> usize {
+        if let Some(&object) = self.scalars.get(&encoded) {
+            return object;
+        }
+        let object = self.push(Entry::Scalar(encoded.clone()));
+        self.scalars.insert(encoded, object);
+        object
+    }
+
+    /// Stores a completed collection in the object reserved for it and returns the
+    /// index of the object to reference.
+    ///
+    /// The elements of a collection are serialized before it is complete, so equal
+    /// collections reference equal objects. When collections are shared, a collection
+    /// equal to one already stored references that object instead, and the reserved
+    /// object is left unreferenced.
+    fn finish_collection(&mut self, object: usize, entry: Entry) -> usize {
+        if self.options.share_collections {
+            if let Some(&shared_object) = self.collections.get(&entry) {
+                return shared_object;
+            }
+            self.collections.insert(entry.clone(), object);
+        }
+        self.objects[object] = entry;
+        object
+    }
+
+    /// Reserves the dictionary of an enum variant and serializes its name, returning
+    /// the tag to complete with the content of the variant.
+    ///
+    /// Enums are externally tagged, as they are deserialized. Variants with content are
+    /// represented by a dictionary with a single entry from the variant name to its content.
+    fn begin_variant(&mut self, variant: &str) -> VariantTag {
+        let object = self.push(Entry::Dictionary(Vec::new()));
+        let key = self.push_scalar(encode_string(variant));
+        VariantTag { object, key }
+    }
+
+    /// Stores the dictionary of an enum variant with its content and returns the index
+    /// of the object to reference.
+    fn finish_variant(&mut self, tag: VariantTag, value: usize) -> usize {
+        self.finish_collection(tag.object, Entry::Dictionary(vec![(tag.key, value)]))
+    }
+
+    /// Returns the objects referenced by a collection, in the order of its references.
+    fn references_of(&self, object: usize) -> Vec<usize> {
+        match &self.objects[object] {
+            Entry::Scalar(_) =>
+                Vec::new(),
+            Entry::Array(objects) =>
+                objects.clone(),
+            Entry::Dictionary(pairs) =>
+                pairs.iter().map(|&(key, _)| key)
+                    .chain(pairs.iter().map(|&(_, value)| value))
+                    .collect(),
+        }
+    }
+
+    /// Returns the objects reachable from the root object in the order they are written.
+    ///
+    /// Objects are written in the configured `ObjectOrder`, visiting the objects
+    /// referenced by each collection in the order of its references. The order
+    /// therefore depends only on the structure of the serialized value.
+    fn write_order(&self, root_object: usize) -> Vec<usize> {
+        let mut visited = vec![false; self.objects.len()];
+        let mut order = Vec::with_capacity(self.objects.len());
+        let mut pending = VecDeque::from(vec![root_object]);
+        while let Some(object) = pending.pop_front() {
+            if visited[object] {
+                continue;
+            }
+            visited[object] = true;
+            order.push(object);
+
+            let references = self.references_of(object);
+            match self.options.object_order {
+                // References are pushed in reverse to be visited in the order they are referenced.
+                ObjectOrder::DepthFirst =>
+                    for reference in references.into_iter().rev() {
+                        pending.push_front(reference);
+                    },
+                ObjectOrder::BreadthFirst =>
+                    pending.extend(references),
+            }
+        }
+        order
+    }
+
+    /// Writes the object table as a complete document with the specified root object.
+    ///
+    /// The header, object table, offset table and trailer are written in order. Objects
+    /// are encoded one at a time as they are written, with the root object first.
+    fn write_document<W>(&self, writer: &mut W, root_object: usize) -> Result<()>
+    where
+        W: io::Write,
+    {
+        match self.objects[root_object] {
+            Entry::Array(_) | Entry::Dictionary(_) => {}
+            Entry::Scalar(_) => return Err(Error::RootObjectNotArrayOrDictionary),
+        }
+
+        // Assign each object its index in the written object table.
+        let order = self.write_order(root_object);
+        let mut index_of = vec![0; self.objects.len()];
+        for (index, &object) in order.iter().enumerate() {
+            index_of[object] = index;
+        }
+
+        // Every object in the table must be addressable by an object reference.
+        let minimum_reference_size = size_of_value(order.len() - 1);
+        let reference_size = self.options.object_reference_size.unwrap_or(minimum_reference_size);
+        if !(1 ..= 8).contains(&reference_size) || minimum_reference_size > reference_size {
+            return Err(Error::Message(format!(
+                "object reference size of {} bytes cannot address {} objects",
+                reference_size,
+                order.len()
+            )));
+        }
+
+        writer.write_all(HEADER_MAGIC_NUMBER)?;
+        writer.write_all(&[HEADER_VERSION_00.0, HEADER_VERSION_00.1])?;
+        let mut offset = HEADER_SIZE;
+
+        // Write the object table, recording the offset of each object.
+        let mut offset_table = Vec::with_capacity(order.len());
+        let mut output = Vec::new();
+        for &object in &order {
+            offset_table.push(offset);
+            output.clear();
+            match &self.objects[object] {
+                Entry::Scalar(encoded) =>
+                    output.extend_from_slice(encoded),
+                Entry::Array(objects) =>
+                    encode_marker(&mut output, ObjectFormat::Array, objects.len()),
+                Entry::Dictionary(pairs) =>
+                    encode_marker(&mut output, ObjectFormat::Dictionary, pairs.len()),
+            }
+            for reference in self.references_of(object) {
+                encode_reference(&mut output, index_of[reference], reference_size);
+            }
+            writer.write_all(&output)?;
+            offset += output.len();
+        }
+
+        // Write the offset table and the trailer which locates it. Like CoreFoundation,
+        // entries are sized to represent the offset of the offset table itself.
+        let offset_table_entry_size = size_of_value(offset);
+        output.clear();
+        for &object_offset in &offset_table {
+            encode_reference(&mut output, object_offset, offset_table_entry_size);
+        }
+        encode_trailer(&mut output, &Trailer {
+            sort_version: 0,
+            offset_table_entry_size,
+            object_reference_size: reference_size,
+            number_of_objects: order.len(),
+            root_object: index_of[root_object],
+            offset_table_offset: offset,
+        });
+        writer.write_all(&output)?;
+
+        Ok(())
+    }
+
+}
+
+/// Encodes an integer object using the narrowest format which can represent the value.
+///
+/// Non-negative values are encoded as unsigned integers of 1, 2 or 4 bytes where they
+/// fit. Negative values and larger values are encoded as 64-bit signed integers.
+fn encode_integer(value: i64) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(9);
+    if let Ok(value) = u8::try_from(value) {
+        encoded.push(ObjectFormat::UInt8.tag_bits());
+        encoded.push(value);
+    } else if let Ok(value) = u16::try_from(value) {
+        encoded.push(ObjectFormat::UInt16.tag_bits());
+        encoded.extend_from_slice(&value.to_be_bytes());
+    } else if let Ok(value) = u32::try_from(value) {
+        encoded.push(ObjectFormat::UInt32.tag_bits());
+        encoded.extend_from_slice(&value.to_be_bytes());
+    } else {
+        encoded.push(ObjectFormat::SInt64.tag_bits());
+        encoded.extend_from_slice(&value.to_be_bytes());
+    }
+    encoded
+}
+
+/// Encodes a date object with the specified `CFAbsoluteTime` value.
+fn encode_date(absolute_time: f64) -> Vec<u8> {
+    let mut encoded = vec![ObjectFormat::Date.tag_bits()];
+    encoded.extend_from_slice(&absolute_time.to_be_bytes());
+    encoded
+}
+
+/// Encodes a UID object using the narrowest of 1, 2, 4 or 8 bytes.
+fn encode_uid(value: u64) -> Vec<u8> {
+    let byte_count = Uid::from_u64(value).byte_len();
+    let mut encoded = vec![ObjectFormat::Uid.tag_bits() | (byte_count - 1) as u8];
+    encoded.extend_from_slice(&value.to_be_bytes()[8 - byte_count ..]);
+    encoded
+}
+
+/// Encodes the marker byte of a variable-length object, followed by an integer object
+/// containing the length if it does not fit in the marker byte.
+fn encode_marker(output: &mut Vec<u8>, format: ObjectFormat, length: usize) {
+    if length < 0b0000_1111 {
+        output.push(format.tag_bits() | length as u8);
+    } else {
+        output.push(format.tag_bits() | 0b0000_1111);
+        output.extend_from_slice(&encode_integer(length as i64));
+    }
+}
+
+/// Returns the number of bytes needed to represent the value as an unsigned integer.
+pub(crate) fn size_of_value(value: usize) -> usize {
+    let significant_bits = usize::BITS - value.leading_zeros();
+    std::cmp::max(1, (significant_bits as usize + 7) / 8)
+}
+
+/// Encodes an object reference or offset table entry of the specified width.
+fn encode_reference(output: &mut Vec<u8>, value: usize, size: usize) {
+    output.extend_from_slice(&(value as u64).to_be_bytes()[8 - size ..]);
+}
+
+/// Encodes a 32-byte trailer.
+fn encode_trailer(output: &mut Vec<u8>, trailer: &Trailer) {
+    output.extend_from_slice(&[0; TRAILER_PREAMBLE_UNUSED_SIZE]);
+    output.push(trailer.sort_version);
+    output.push(trailer.offset_table_entry_size as u8);
+    output.push(trailer.object_reference_size as u8);
+    output.extend_from_slice(&(trailer.number_of_objects as u64).to_be_bytes());
+    output.extend_from_slice(&(trailer.root_object as u64).to_be_bytes());
+    output.extend_from_slice(&(trailer.offset_table_offset as u64).to_be_bytes());
+}
+
+/// Encodes a string as an ASCII string object if possible, or a UTF-16 string object otherwise.
+fn encode_string(value: &str) -> Vec<u8> {
+    let mut encoded = Vec::new();
+    if value.is_ascii() {
+        encode_marker(&mut encoded, ObjectFormat::AsciiString, value.len());
+        encoded.extend_from_slice(value.as_bytes());
+    } else {
+        let code_units = value.encode_utf16().collect::<Vec<u16>>();
+        encode_marker(&mut encoded, ObjectFormat::Utf16String, code_units.len());
+        for code_unit in code_units {
+            encoded.extend_from_slice(&code_unit.to_be_bytes());
+        }
+    }
+    encoded
+}
+
+impl<'a> ser::Serializer for &'a mut Serializer {
+    type Ok = usize;
+    type Error = Error;
+
+    type SerializeSeq = SerializeArray<'a>;
+    type SerializeTuple = SerializeArray<'a>;
+    type SerializeTupleStruct = SerializeArray<'a>;
+    type SerializeTupleVariant = SerializeArray<'a>;
+    type SerializeMap = SerializeDictionary<'a>;
+    type SerializeStruct = SerializeStructure<'a>;
+    type SerializeStructVariant = SerializeDictionary<'a>;
+
+    fn serialize_bool(self, value: bool) -> Result<usize> {
+        Ok(self.push_scalar(vec![ObjectFormat::Boolean.tag_bits() | value as u8]))
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<usize> {
+        self.serialize_i64(i64::from(value))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<usize> {
+        self.serialize_i64(i64::from(value))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<usize> {
+        self.serialize_i64(i64::from(value))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<usize> {
+        Ok(self.push_scalar(encode_integer(value)))
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<usize> {
+        self.serialize_i64(i64::from(value))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<usize> {
+        self.serialize_i64(i64::from(value))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<usize> {
+        self.serialize_i64(i64::from(value))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<usize> {
+        // Integers are stored as signed values. As in CoreFoundation, values greater than
+        // `i64::MAX` are written as 128-bit integers rather than as negative 64-bit ones.
+        if let Ok(value) = i64::try_from(value) {
+            return self.serialize_i64(value);
+        }
+        let mut encoded = vec![ObjectFormat::SInt128.tag_bits()];
+        encoded.extend_from_slice(&u128::from(value).to_be_bytes());
+        Ok(self.push_scalar(encoded))
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<usize> {
+        let mut encoded = vec![ObjectFormat::Float32.tag_bits()];
+        encoded.extend_from_slice(&value.to_be_bytes());
+        Ok(self.push_scalar(encoded))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<usize> {
+        let mut encoded = vec![ObjectFormat::Float64.tag_bits()];
+        encoded.extend_from_slice(&value.to_be_bytes());
+        Ok(self.push_scalar(encoded))
+    }
+
+    fn serialize_char(self, value: char) -> Result<usize> {
+        self.serialize_str(value.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<usize> {
+        Ok(self.push_scalar(encode_string(value)))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<usize> {
+        let mut encoded = Vec::new();
+        encode_marker(&mut encoded, ObjectFormat::Data, value.len());
+        encoded.extend_from_slice(value);
+        Ok(self.push_scalar(encoded))
+    }
+
+    // There is no null object, so the absence of a value is written as a fill byte.
+    fn serialize_none(self) -> Result<usize> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<usize>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<usize> {
+        Ok(self.push_scalar(vec![ObjectFormat::Fill.tag_bits()]))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<usize> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<usize> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<usize>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<usize>
+    where
+        T: Serialize + ?Sized,
+    {
+        let tag = self.begin_variant(variant);
+        let value = value.serialize(&mut *self)?;
+        Ok(self.finish_variant(tag, value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray<'a>> {
+        Ok(SerializeArray::new(self, len.unwrap_or(0)))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray<'a>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray<'a>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray<'a>> {
+        let tag = self.begin_variant(variant);
+        let mut array = SerializeArray::new(self, len);
+        array.tag = Some(tag);
+        Ok(array)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeDictionary<'a>> {
+        Ok(SerializeDictionary::new(self, len.unwrap_or(0)))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<SerializeStructure<'a>> {
+        // Date and UID objects are represented by pseudo-structures with private names.
+        match name {
+            date::STRUCT_NAME =>
+                Ok(SerializeStructure::Date(self, None)),
+            uid::STRUCT_NAME =>
+                Ok(SerializeStructure::Uid(self, None)),
+            _ =>
+                Ok(SerializeStructure::Dictionary(SerializeDictionary::new(self, len))),
+        }
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeDictionary<'a>> {
+        let tag = self.begin_variant(variant);
+        let mut dictionary = SerializeDictionary::new(self, len);
+        dictionary.tag = Some(tag);
+        Ok(dictionary)
+    }
+}
+
+/// Serializer for the elements of an array, which may be the content of an enum variant.
+struct SerializeArray<'a> {
+    ser: &'a mut Serializer,
+    object: usize,
+    objects: Vec<usize>,
+    tag: Option<VariantTag>,
+}
+
+impl<'a> SerializeArray<'a> {
+    fn new(ser: &'a mut Serializer, capacity: usize) -> Self {
+        let object = ser.push(Entry::Array(Vec::new()));
+        SerializeArray {
+            ser,
+            object,
+            objects: Vec::with_capacity(capacity),
+            tag: None,
+        }
+    }
+
+    fn push<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let object = value.serialize(&mut *self.ser)?;
+        self.objects.push(object);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<usize> {
+        let object = self.ser.finish_collection(self.object, Entry::Array(self.objects));
+        Ok(match self.tag {
+            Some(tag) => self.ser.finish_variant(tag, object),
+            None => object,
+        })
+    }
+}
+
+impl<'a> ser::SerializeSeq for SerializeArray<'a> {
+    type Ok = usize;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<usize> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTuple for SerializeArray<'a> {
+    type Ok = usize;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<usize> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for SerializeArray<'a> {
+    type Ok = usize;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<usize> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTupleVariant for SerializeArray<'a> {
+    type Ok = usize;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<usize> {
+        self.finish()
+    }
+}
+
+/// Serializer for the entries of a dictionary, which may be the content of an enum variant.
+struct SerializeDictionary<'a> {
+    ser: &'a mut Serializer,
+    object: usize,
+    pairs: Vec<(usize, usize)>,
+    next_key: Option<usize>,
+    tag: Option<VariantTag>,
+}
+
+impl<'a> SerializeDictionary<'a> {
+    fn new(ser: &'a mut Serializer, capacity: usize) -> Self {
+        let object = ser.push(Entry::Dictionary(Vec::new()));
+        SerializeDictionary {
+            ser,
+            object,
+            pairs: Vec::with_capacity(capacity),
+            next_key: None,
+            tag: None,
+        }
+    }
+
+    /// Stores the entries of the dictionary, ordered by the encoding of their keys.
+    ///
+    /// Scalar keys are ordered by their encoded bytes, followed by collection keys in
+    /// the order they were serialized. This makes the output independent of the order
+    /// in which a map yields its entries, so equal values serialize identically. The
+    /// entries are left in the order they were serialized if the options require it.
+    fn finish(mut self) -> Result<usize> {
+        if !self.ser.options.preserve_entry_order {
+            let objects = &self.ser.objects;
+            self.pairs.sort_by(|&(lhs, _), &(rhs, _)| {
+                match (&objects[lhs], &objects[rhs]) {
+                    (Entry::Scalar(lhs), Entry::Scalar(rhs)) => lhs.cmp(rhs),
+                    (Entry::Scalar(_), _) => Ordering::Less,
+                    (_, Entry::Scalar(_)) => Ordering::Greater,
+                    _ => Ordering::Equal,
+                }
+            });
+        }
+        let object = self.ser.finish_collection(self.object, Entry::Dictionary(self.pairs));
+        Ok(match self.tag {
+            Some(tag) => self.ser.finish_variant(tag, object),
+            None => object,
+        })
+    }
+
+    /// Serializes an entry keyed by the name of a field.
+    fn push_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = key.serialize(&mut *self.ser)?;
+        let value = value.serialize(&mut *self.ser)?;
+        self.pairs.push((key, value));
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeMap for SerializeDictionary<'a> {
+    type Ok = usize;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.next_key = Some(key.serialize(&mut *self.ser)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = self.next_key.take()
+            .expect("serialize_value called before serialize_key");
+        let value = value.serialize(&mut *self.ser)?;
+        self.pairs.push((key, value));
+        Ok(())
+    }
+
+    fn end(self) -> Result<usize> {
+        self.finish()
+    }
+}
+
+/// Serializer for a structure, which is either a dictionary or a pseudo-structure
+/// representing a Date or UID object.
+enum SerializeStructure<'a> {
+    Dictionary(SerializeDictionary<'a>),
+    Date(&'a mut Serializer, Option<f64>),
+    Uid(&'a mut Serializer, Option<u64>),
+}
+
+/// Returns the error for a pseudo-structure without exactly the expected field.
+fn invalid_pseudo_structure(name: &str) -> Error {
+    Error::Message(format!("invalid {} pseudo-structure", name))
+}
+
+impl<'a> ser::SerializeStruct for SerializeStructure<'a> {
+    type Ok = usize;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        match self {
+            SerializeStructure::Dictionary(dictionary) =>
+                dictionary.push_field(key, value)?,
+            SerializeStructure::Date(_, absolute_time) => {
+                if key != date::STRUCT_FIELD || absolute_time.is_some() {
+                    return Err(invalid_pseudo_structure("date"));
+                }
+                *absolute_time = Some(value.serialize(NumberSerializer)?.as_f64());
+            }
+            SerializeStructure::Uid(_, uid) => {
+                if key != uid::STRUCT_FIELD || uid.is_some() {
+                    return Err(invalid_pseudo_structure("uid"));
+                }
+                let value = value.serialize(NumberSerializer)?.as_u64()
+                    .ok_or_else(|| invalid_pseudo_structure("uid"))?;
+                *uid = Some(value);
+            }
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<usize> {
+        match self {
+            SerializeStructure::Dictionary(dictionary) =>
+                dictionary.finish(),
+            SerializeStructure::Date(ser, Some(absolute_time)) =>
+                Ok(ser.push_scalar(encode_date(absolute_time))),
+            SerializeStructure::Uid(ser, Some(uid)) =>
+                Ok(ser.push_scalar(encode_uid(uid))),
+            SerializeStructure::Date(_, None) =>
+                Err(invalid_pseudo_structure("date")),
+            SerializeStructure::Uid(_, None) =>
+                Err(invalid_pseudo_structure("uid")),
+        }
+    }
+}
+
+impl<'a> ser::SerializeStructVariant for SerializeDictionary<'a> {
+    type Ok = usize;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.push_field(key, value)
+    }
+
+    fn end(self) -> Result<usize> {
+        self.finish()
+    }
+}
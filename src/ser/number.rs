@@ -0,0 +1,213 @@
+//
+// Copyright 2020 bplist Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+//
+
+use serde::ser::{self, Impossible, Serialize};
+
+use crate::error::{Error, Result};
+
+/// A numeric value captured from the field of a Date-type or UID-type pseudo-structure.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Number {
+    Signed(i64),
+    Unsigned(u64),
+    Float(f64),
+}
+
+impl Number {
+    /// Returns the value as a double-precision floating point value.
+    pub fn as_f64(self) -> f64 {
+        match self {
+            Number::Signed(value) => value as f64,
+            Number::Unsigned(value) => value as f64,
+            Number::Float(value) => value,
+        }
+    }
+
+    /// Returns the value as an unsigned integer, if it is a non-negative integer.
+    pub fn as_u64(self) -> Option<u64> {
+        match self {
+            Number::Signed(value) => (value >= 0).then_some(value as u64),
+            Number::Unsigned(value) => Some(value),
+            Number::Float(_) => None,
+        }
+    }
+}
+
+/// Serializer which captures a single numeric value and rejects everything else.
+pub struct NumberSerializer;
+
+/// Returns the error for a value which is not a number.
+fn expected_number() -> Error {
+    Error::Message(String::from("expected a numeric field value"))
+}
+
+impl ser::Serializer for NumberSerializer {
+    type Ok = Number;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<Number, Error>;
+    type SerializeTuple = Impossible<Number, Error>;
+    type SerializeTupleStruct = Impossible<Number, Error>;
+    type SerializeTupleVariant = Impossible<Number, Error>;
+    type SerializeMap = Impossible<Number, Error>;
+    type SerializeStruct = Impossible<Number, Error>;
+    type SerializeStructVariant = Impossible<Number, Error>;
+
+    fn serialize_bool(self, _value: bool) -> Result<Number> {
+        Err(expected_number())
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<Number> {
+        Ok(Number::Signed(i64::from(value)))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<Number> {
+        Ok(Number::Signed(i64::from(value)))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<Number> {
+        Ok(Number::Signed(i64::from(value)))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<Number> {
+        Ok(Number::Signed(value))
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<Number> {
+        Ok(Number::Unsigned(u64::from(value)))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<Number> {
+        Ok(Number::Unsigned(u64::from(value)))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<Number> {
+        Ok(Number::Unsigned(u64::from(value)))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<Number> {
+        Ok(Number::Unsigned(value))
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<Number> {
+        Ok(Number::Float(f64::from(value)))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<Number> {
+        Ok(Number::Float(value))
+    }
+
+    fn serialize_char(self, _value: char) -> Result<Number> {
+        Err(expected_number())
+    }
+
+    fn serialize_str(self, _value: &str) -> Result<Number> {
+        Err(expected_number())
+    }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<Number> {
+        Err(expected_number())
+    }
+
+    fn serialize_none(self) -> Result<Number> {
+        Err(expected_number())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Number>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Number> {
+        Err(expected_number())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Number> {
+        Err(expected_number())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<Number> {
+        Err(expected_number())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Number>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Number>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(expected_number())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(expected_number())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(expected_number())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(expected_number())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(expected_number())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(expected_number())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct> {
+        Err(expected_number())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(expected_number())
+    }
+}
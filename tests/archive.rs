@@ -0,0 +1,507 @@
+
+use bplist::{
+    Archive, DecodeStats, Deserializer, DocumentReport, KeyedArchiveInfo, Object, ObjectRef, Warning,
+};
+use bplist::object::Uid;
+use serde::Deserialize;
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+
+fn read_fixture(name: &str) -> Vec<u8> {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests");
+    plist_path.push(name);
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn test_archive_object_offsets() {
+    let data = read_fixture("point.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    let offsets = archive.object_offsets();
+    assert_eq!(offsets, &[0x08, 0x0D, 0x0F, 0x11, 0x13]);
+    assert_eq!(archive.object_table_range(), 0x08 .. 0x15);
+
+    // Offsets need not be sorted or within the object table, but they are here.
+    assert!(offsets.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(offsets.iter().all(|offset| archive.object_table_range().contains(offset)));
+}
+
+#[test]
+fn test_archive_uids() {
+    let data = read_fixture("uids.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    assert_eq!(
+        archive.uids(),
+        Ok(vec![Uid(1), Uid(2), Uid(3), Uid(0)])
+    );
+}
+
+#[test]
+fn test_archive_uids_are_reported_per_object() {
+    // The serializer writes equal UIDs as one object, referenced from both arrays.
+    let data = bplist::to_bytes(&vec![vec![Uid(1), Uid(2)], vec![Uid(1)]]).unwrap();
+    let archive = Archive::from_slice(&data).unwrap();
+    assert_eq!(archive.uids(), Ok(vec![Uid(1), Uid(2)]));
+
+    // An array of two distinct UID objects with the same value.
+    let data = vec![
+        0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+        0xA2, 0x01, 0x02, 0x80, 0x01, 0x80, 0x01, 0x08,
+        0x0B, 0x0D, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x0F,
+    ];
+    let archive = Archive::from_slice(&data).unwrap();
+    assert_eq!(archive.uids(), Ok(vec![Uid(1), Uid(1)]));
+}
+
+#[test]
+fn test_archive_uids_none_present() {
+    let data = read_fixture("point.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    assert_eq!(archive.uids(), Ok(vec![]));
+}
+
+#[test]
+fn test_archive_validate_references() {
+    let data = read_fixture("dangling_references.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    assert_eq!(archive.validate_references(), vec![(0, 5), (2, 9), (3, 200)]);
+}
+
+#[test]
+fn test_archive_validate_references_skips_malformed_objects() {
+    // Object 1 has an unsupported marker, and the dictionary at object 4 is truncated.
+    let data = read_fixture("malformed_dangling_references.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    assert!(bplist::from_slice::<bplist::Object>(&data).is_err());
+    assert_eq!(archive.validate_references(), vec![(0, 5), (2, 9), (3, 200)]);
+}
+
+#[test]
+fn test_archive_validate_references_none_dangling() {
+    let data = read_fixture("point.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    assert_eq!(archive.validate_references(), vec![]);
+}
+
+#[test]
+fn test_archive_root_array_ref_borrows_strings() {
+    let data = read_fixture("string_list.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    let objects = archive.root_array_ref().unwrap();
+    assert_eq!(
+        objects,
+        vec![
+            ObjectRef::String(Cow::Borrowed("alpha")),
+            ObjectRef::String(Cow::Borrowed("beta")),
+            ObjectRef::String(Cow::Borrowed("gamma")),
+        ]
+    );
+
+    // Each element is borrowed directly from the input.
+    let input_range = data.as_ptr_range();
+    for object in objects {
+        match object {
+            ObjectRef::String(Cow::Borrowed(string)) =>
+                assert!(input_range.contains(&string.as_ptr())),
+            other =>
+                panic!("expected a borrowed string, found {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_archive_root_array_ref_borrows_mixed_payloads() {
+    let data = read_fixture("mixed_list.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    let objects = archive.root_array_ref().unwrap();
+    assert_eq!(
+        objects,
+        vec![
+            ObjectRef::String(Cow::Borrowed("alpha")),
+            ObjectRef::Data(Cow::Borrowed(&[0x01, 0x02, 0x03])),
+            ObjectRef::Integer(7),
+            ObjectRef::Boolean(true),
+        ]
+    );
+
+    // String and data payloads are borrowed from the input rather than copied.
+    let input_range = data.as_ptr_range();
+    match &objects[0] {
+        ObjectRef::String(Cow::Borrowed(string)) =>
+            assert!(input_range.contains(&string.as_ptr())),
+        other =>
+            panic!("expected a borrowed string, found {:?}", other),
+    }
+    match &objects[1] {
+        ObjectRef::Data(Cow::Borrowed(bytes)) =>
+            assert!(input_range.contains(&bytes.as_ptr())),
+        other =>
+            panic!("expected borrowed data, found {:?}", other),
+    }
+}
+
+#[test]
+fn test_archive_root_array_ref_with_dictionary_root() {
+    let data = read_fixture("point.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    assert_eq!(archive.root_array_ref(), Err(bplist::Error::ExpectedArray));
+}
+
+#[test]
+fn test_archive_get_at_point() {
+    let data = read_fixture("point.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    assert_eq!(archive.get_at::<u64>("/x"), Ok(1));
+    assert_eq!(archive.get_at::<u64>("/y"), Ok(20));
+}
+
+#[test]
+fn test_archive_get_at_nested() {
+    let data = read_fixture("nested.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    assert_eq!(archive.get_at::<String>("/name"), Ok(String::from("origin")));
+    assert_eq!(archive.get_at::<u64>("/points/1/y"), Ok(40));
+    assert_eq!(archive.get_at::<bool>("/a~1b/~0"), Ok(true));
+    assert_eq!(archive.get_at::<Vec<u64>>(""), Err(bplist::Error::Message(
+        String::from("invalid type: map, expected a sequence")
+    )));
+}
+
+#[test]
+fn test_archive_get_at_missing() {
+    let data = read_fixture("nested.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    assert_eq!(
+        archive.get_at::<u64>("/points/2/x"),
+        Err(bplist::Error::Message(String::from("no object found at `/points/2/x`")))
+    );
+    assert_eq!(
+        archive.get_at::<u64>("/name/0"),
+        Err(bplist::Error::Message(String::from("no object found at `/name/0`")))
+    );
+    assert_eq!(
+        archive.get_at::<u64>("name"),
+        Err(bplist::Error::Message(String::from("invalid pointer `name`")))
+    );
+}
+
+#[test]
+fn test_from_slice_with_report_unreachable() {
+    let data = read_fixture("unreachable.plist");
+    let (values, report) = bplist::from_slice_with_report::<Vec<u8>>(&data).unwrap();
+
+    assert_eq!(values, vec![7]);
+    assert_eq!(report, DocumentReport {
+        number_of_objects: 4,
+        unreachable_objects: vec![2, 3],
+    });
+}
+
+#[test]
+fn test_from_slice_with_report_all_reachable() {
+    let data = read_fixture("uids.plist");
+    let (_, report) = bplist::from_slice_with_report::<bplist::Object>(&data).unwrap();
+    assert_eq!(report.unreachable_objects, Vec::<usize>::new());
+}
+
+#[test]
+fn test_from_slice_with_stats_shared_string() {
+    let data = read_fixture("shared_string.plist");
+    let (values, stats) = bplist::from_slice_with_stats::<Vec<String>>(&data).unwrap();
+
+    assert_eq!(values, vec!["shared", "unique", "shared"]);
+    assert_eq!(stats, DecodeStats { shared_object_references: 1 });
+}
+
+#[test]
+fn test_deserializer_stats_no_sharing() {
+    let data = read_fixture("point.plist");
+    let mut deserializer = Deserializer::from_slice(&data);
+    Object::deserialize(&mut deserializer).unwrap();
+    assert_eq!(deserializer.stats().shared_object_references, 0);
+}
+
+#[test]
+fn test_archive_decode_lazy_field() {
+    let data = read_fixture("point.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    assert_eq!(archive.decode_lazy_field::<u64>("y"), Ok(Some(20)));
+    assert_eq!(archive.decode_lazy_field::<u64>("z"), Ok(None));
+    assert!(archive.decode_lazy_field::<String>("y").is_err());
+}
+
+#[test]
+fn test_archive_decode_lazy_field_with_array_root() {
+    let data = read_fixture("integer_list.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    assert_eq!(archive.decode_lazy_field::<u64>("y"), Err(bplist::Error::ExpectedDictionary));
+}
+
+#[test]
+fn test_archive_keyed_archive_info() {
+    let data = read_fixture("keyed_archive.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    let mut top = BTreeMap::new();
+    top.insert(String::from("root"), Uid(1));
+    assert_eq!(archive.keyed_archive_info(), Ok(Some(KeyedArchiveInfo {
+        archiver: String::from("NSKeyedArchiver"),
+        version: 100000,
+        top,
+    })));
+}
+
+#[test]
+fn test_archive_keyed_archive_info_not_archive() {
+    let data = read_fixture("point.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+    assert_eq!(archive.keyed_archive_info(), Ok(None));
+
+    let data = read_fixture("uids.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+    assert_eq!(archive.keyed_archive_info(), Ok(None));
+}
+
+#[test]
+fn test_is_keyed_archive() {
+    let data = read_fixture("keyed_archive.plist");
+    assert_eq!(bplist::is_keyed_archive(&data), Ok(true));
+    assert_eq!(Archive::from_slice(&data).unwrap().is_keyed_archive(), Ok(true));
+
+    // A dictionary without the archive keys, and an array.
+    let data = read_fixture("point.plist");
+    assert_eq!(bplist::is_keyed_archive(&data), Ok(false));
+    let data = read_fixture("integer_list.plist");
+    assert_eq!(bplist::is_keyed_archive(&data), Ok(false));
+}
+
+#[test]
+fn test_archive_reencode_with_reference_size() {
+    let data = read_fixture("nested.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+    let expected = bplist::from_slice::<Object>(&data).unwrap();
+
+    for &size in &[1, 3, 8] {
+        let reencoded = archive.reencode_with_reference_size(size).unwrap();
+        assert_eq!(bplist::inspect(&reencoded).unwrap().trailer.object_reference_size, size);
+        assert_eq!(bplist::from_slice::<Object>(&reencoded), Ok(expected.clone()));
+    }
+}
+
+#[test]
+fn test_archive_reencode_with_reference_size_too_small() {
+    let data = bplist::to_bytes(&(0 .. 300).collect::<Vec<u32>>()).unwrap();
+    let archive = Archive::from_slice(&data).unwrap();
+
+    assert_eq!(
+        archive.reencode_with_reference_size(1),
+        Err(bplist::Error::Message(String::from(
+            "object reference size of 1 bytes cannot address 301 objects"
+        )))
+    );
+    assert!(archive.reencode_with_reference_size(2).is_ok());
+    assert!(archive.reencode_with_reference_size(9).is_err());
+}
+
+#[test]
+fn test_archive_ordered_entries() {
+    let data = read_fixture("unsorted_keys.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    assert_eq!(
+        archive.ordered_entries::<String, u8>(0),
+        Ok(vec![
+            (String::from("zeta"), 1),
+            (String::from("alpha"), 2),
+            (String::from("mid"), 3),
+        ])
+    );
+    assert_eq!(archive.ordered_entries::<String, u8>(1), Err(bplist::Error::ExpectedDictionary));
+}
+
+#[test]
+fn test_archive_reserialize_with_edit_preserves_entry_order() {
+    let data = read_fixture("unsorted_keys.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+
+    let edited = archive.reserialize_with_edit(|object| {
+        if let Object::Dictionary(map) = object {
+            map.remove(&Object::from("alpha"));
+            map.insert(Object::from("beta"), Object::Integer(4));
+            map.insert(Object::from("mid"), Object::Integer(5));
+        }
+        Ok(())
+    });
+    let edited = Archive::from_slice(edited.as_ref().unwrap()).unwrap();
+    assert_eq!(
+        edited.ordered_entries::<String, u8>(0),
+        Ok(vec![
+            (String::from("zeta"), 1),
+            (String::from("mid"), 5),
+            (String::from("beta"), 4),
+        ])
+    );
+}
+
+#[test]
+fn test_archive_repeated_access() {
+    let data = read_fixture("nested.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+    let expected = bplist::from_slice::<Object>(&data).unwrap();
+
+    // Repeated access decodes the same objects as the first.
+    for _ in 0 .. 3 {
+        assert_eq!(archive.get_at::<Object>(""), Ok(expected.clone()));
+    }
+}
+
+#[test]
+fn test_archive_is_send_and_sync() {
+    fn assert_send_and_sync<T: Send + Sync>() {}
+    assert_send_and_sync::<Archive>();
+    assert_send_and_sync::<bplist::Deserializer>();
+}
+
+#[test]
+fn test_archive_repeated_access_to_invalid_object() {
+    let mut data = bplist::to_bytes(&vec![1u8, 2]).unwrap();
+    let archive = Archive::from_slice(&data).unwrap();
+    let offset = archive.object_offsets()[2];
+    data[offset] = 0x70;
+
+    let archive = Archive::from_slice(&data).unwrap();
+    for _ in 0 .. 3 {
+        assert_eq!(
+            archive.get_at::<Vec<u8>>(""),
+            Err(bplist::Error::At {
+                offset,
+                source: Box::new(bplist::Error::InvalidOrUnsupportedObjectFormat),
+            })
+        );
+    }
+    assert_eq!(archive.get_at::<u8>("/0"), Ok(1));
+}
+
+#[test]
+fn test_deserializer_reparses_document_when_options_change() {
+    let mut data = read_fixture("point.plist");
+    let sort_version_offset = data.len() - 32 + 5;
+    data[sort_version_offset] = 1;
+
+    let mut deserializer = bplist::Deserializer::from_slice(&data);
+    assert!(Object::deserialize(&mut deserializer).is_ok());
+    assert!(Object::deserialize(&mut deserializer).is_ok());
+
+    let mut deserializer = deserializer.with_strict_sort_version(true);
+    assert_eq!(
+        Object::deserialize(&mut deserializer),
+        Err(bplist::Error::UnsupportedSortVersion)
+    );
+}
+
+#[test]
+fn test_archive_sort_version() {
+    let mut data = read_fixture("point.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+    assert_eq!(archive.sort_version(), 0);
+
+    // The sort version is the sixth byte of the trailer.
+    let sort_version_offset = data.len() - 32 + 5;
+    data[sort_version_offset] = 1;
+
+    let archive = Archive::from_slice(&data).unwrap();
+    assert_eq!(archive.sort_version(), 1);
+    assert_eq!(bplist::inspect(&data).unwrap().trailer.sort_version, 1);
+
+    // The value is ignored unless strict checking is requested.
+    let mut deserializer = Deserializer::from_slice(&data);
+    assert!(Object::deserialize(&mut deserializer).is_ok());
+
+    let mut deserializer = Deserializer::from_slice(&data).with_strict_sort_version(true);
+    assert_eq!(Object::deserialize(&mut deserializer), Err(bplist::Error::UnsupportedSortVersion));
+}
+
+#[test]
+fn test_archive_data_chunks() {
+    let data = read_fixture("large_data.plist");
+    let archive = Archive::from_slice(&data).unwrap();
+    let expected = (0 .. 1000).map(|i| (i % 251) as u8).collect::<Vec<u8>>();
+
+    // The 1000-byte data object is the only element of the root array.
+    let chunks = archive.data_chunks(1, 64).unwrap().collect::<Vec<&[u8]>>();
+    assert_eq!(chunks.len(), 16);
+    assert!(chunks[.. 15].iter().all(|chunk| chunk.len() == 64));
+    assert_eq!(chunks[15].len(), 40);
+    assert_eq!(chunks.concat(), expected);
+
+    // Each chunk is borrowed directly from the input.
+    let input_range = data.as_ptr_range();
+    assert!(chunks.iter().all(|chunk| input_range.contains(&chunk.as_ptr())));
+
+    assert_eq!(archive.data_chunks(0, 64).err(), Some(bplist::Error::ExpectedData));
+}
+
+#[test]
+fn test_read_header_and_trailer() {
+    let data = read_fixture("integer_list.plist");
+
+    assert_eq!(bplist::read_header(&data), Ok(bplist::Header { version: (b'0', b'0') }));
+
+    let trailer = bplist::read_trailer(&data).unwrap();
+    assert_eq!(trailer, bplist::inspect(&data).unwrap().trailer);
+    assert_eq!(trailer.root_object, 0);
+    assert_eq!(trailer.object_reference_size, 1);
+
+    assert_eq!(bplist::read_header(&data[.. 4]), Err(bplist::Error::Eof));
+    assert_eq!(bplist::read_header(&data[1 ..]), Err(bplist::Error::MissingOrInvalidHeader));
+    assert_eq!(bplist::read_trailer(&data[data.len() - 31 ..]), Err(bplist::Error::Eof));
+}
+
+#[test]
+fn test_from_slice_with_warnings() {
+    // The root array references a 2-byte integer 5 and the integer 7 with 2-byte
+    // references, and an unreferenced string follows.
+    let data = read_fixture("non_minimal.plist");
+    let (values, warnings) = bplist::from_slice_with_warnings::<Vec<u64>>(&data).unwrap();
+
+    assert_eq!(values, vec![5, 7]);
+    assert_eq!(warnings, vec![
+        Warning::OversizedObjectReferences { size: 2, minimum_size: 1 },
+        Warning::NonMinimalInteger { object: 1 },
+        Warning::UnreachableObject { object: 3 },
+    ]);
+}
+
+#[test]
+fn test_from_slice_with_warnings_none() {
+    let data = read_fixture("point.plist");
+    let (_, warnings) = bplist::from_slice_with_warnings::<Object>(&data).unwrap();
+    assert_eq!(warnings, vec![]);
+}
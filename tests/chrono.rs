@@ -0,0 +1,31 @@
+#![cfg(feature = "chrono")]
+
+use bplist::Date;
+use chrono::{DateTime, TimeZone, Utc};
+
+use std::convert::TryFrom;
+
+#[test]
+fn test_date_from_chrono() {
+    // 1 January 2020, 00:00:00 UTC is 599529600 seconds after the Core Data Epoch.
+    let time = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(Date::from(time), Date { absolute_time: 599_529_600.0.into() });
+
+    let time = Utc.with_ymd_and_hms(2001, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(Date::from(time), Date { absolute_time: 0.0.into() });
+}
+
+#[test]
+fn test_chrono_from_date() {
+    let date = Date { absolute_time: 599_529_600.5.into() };
+    let time = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()
+        + chrono::Duration::milliseconds(500);
+    assert_eq!(DateTime::<Utc>::try_from(date), Ok(time));
+
+    // Dates before the Unix epoch round-trip as well.
+    let time = Utc.with_ymd_and_hms(1969, 7, 20, 20, 17, 40).unwrap();
+    assert_eq!(DateTime::<Utc>::try_from(Date::from(time)), Ok(time));
+
+    assert!(DateTime::<Utc>::try_from(Date { absolute_time: f64::NAN.into() }).is_err());
+    assert!(DateTime::<Utc>::try_from(Date { absolute_time: 1e300.into() }).is_err());
+}
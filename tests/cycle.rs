@@ -17,3 +17,59 @@ fn test_deserialize_input_with_cycle() {
         Err(bplist::Error::CycleDetected)
     );
 }
+
+#[test]
+fn test_cycle_detected_when_ignored() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/cycle.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(
+        bplist::from_slice::<serde::de::IgnoredAny>(&data),
+        Err(bplist::Error::CycleDetected)
+    );
+}
+
+#[test]
+fn test_shared_array_at_lower_index_is_not_a_cycle() {
+    // The root array at index 1 references the array at index 0 twice.
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/shared_lower_index_array.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(
+        bplist::from_slice::<Vec<Vec<u8>>>(&data),
+        Ok(vec![vec![7], vec![7]])
+    );
+    assert!(bplist::from_slice::<serde::de::IgnoredAny>(&data).is_ok());
+}
+
+#[test]
+fn test_validate_input_with_cycle() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/cycle.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(bplist::validate(&data), Err(bplist::Error::CycleDetected));
+}
+
+#[test]
+fn test_validate_shared_array_at_lower_index() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/shared_lower_index_array.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(bplist::validate(&data), Ok(()));
+}
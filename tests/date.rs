@@ -0,0 +1,168 @@
+
+use bplist::Object;
+use bplist::object::{Date, SystemTimeFromDate};
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[derive(PartialEq, Serialize, Deserialize, Debug)]
+struct Event {
+    created: Date,
+}
+
+#[derive(PartialEq, Deserialize, Debug)]
+struct EventWithSystemTime {
+    created: SystemTimeFromDate,
+}
+
+#[test]
+fn test_deserialize_date() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/date.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    // 1 January 2020, 00:00:00 UTC.
+    assert_eq!(
+        bplist::from_slice::<Event>(&data),
+        Ok(Event {
+            created: Date {
+                absolute_time: 599_529_600.0.into()
+            }
+        })
+    );
+}
+
+#[test]
+fn test_deserialize_date_as_system_time() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/date.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    // 1 January 2020, 00:00:00 UTC.
+    assert_eq!(
+        bplist::from_slice::<EventWithSystemTime>(&data),
+        Ok(EventWithSystemTime {
+            created: SystemTimeFromDate(UNIX_EPOCH + Duration::from_secs(1_577_836_800))
+        })
+    );
+}
+
+#[test]
+fn test_deserialize_date_as_object() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/date.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(
+        bplist::from_slice::<Object>(&data),
+        Ok(Object::Dictionary({
+            let mut map = BTreeMap::new();
+            map.insert(
+                Object::String(String::from("created")),
+                Object::Date(Date { absolute_time: 599_529_600.0.into() })
+            );
+            map
+        }))
+    );
+}
+
+#[test]
+fn test_deserialize_date_field_name_as_dictionary_key() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/date_field_name.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    // A user key equal to the name of the private Date field is not a Date.
+    assert_eq!(
+        bplist::from_slice::<Object>(&data),
+        Ok(Object::Dictionary({
+            let mut map = BTreeMap::new();
+            map.insert(
+                Object::String(String::from("$__bplist_private_Date_absolute_time")),
+                Object::Real(1.5.into())
+            );
+            map
+        }))
+    );
+}
+
+#[test]
+fn test_serialize_date_roundtrip() {
+    let event = Event {
+        created: Date {
+            absolute_time: 40.13.into()
+        }
+    };
+    let bytes = bplist::to_bytes(&event).unwrap();
+
+    // The field value is encoded as a Date object, rather than a dictionary.
+    let date_marker = [&[0b0011_0011][..], &40.13f64.to_be_bytes()[..]].concat();
+    assert!(bytes.windows(date_marker.len()).any(|window| window == &date_marker[..]));
+    assert_eq!(bplist::from_slice::<Event>(&bytes), Ok(event));
+}
+
+#[test]
+fn test_date_system_time_conversion() {
+    let times = vec![
+        UNIX_EPOCH,
+        UNIX_EPOCH + Duration::from_millis(1_500),
+        UNIX_EPOCH - Duration::from_millis(1_500),
+        UNIX_EPOCH - Duration::from_secs(86_400 * 365),
+        UNIX_EPOCH + Duration::from_secs(978_307_200),
+    ];
+    for time in times {
+        let date = Date::from_system_time(time);
+        assert_eq!(date.to_system_time(), Some(time), "{:?}", date);
+    }
+
+    // The epochs differ by 978307200 seconds.
+    assert_eq!(
+        Date::from_system_time(UNIX_EPOCH),
+        Date { absolute_time: (-978_307_200.0).into() }
+    );
+    assert_eq!(
+        Date::from_system_time(UNIX_EPOCH - Duration::from_secs(1)),
+        Date { absolute_time: (-978_307_201.0).into() }
+    );
+
+    // Dates which cannot be represented as a `SystemTime` are rejected.
+    assert_eq!(Date { absolute_time: f64::NAN.into() }.to_system_time(), None);
+    assert_eq!(Date { absolute_time: f64::INFINITY.into() }.to_system_time(), None);
+}
+
+#[test]
+fn test_date_to_rfc3339() {
+    let cases = vec![
+        (0.0, "2001-01-01T00:00:00Z"),
+        (599_529_600.0, "2020-01-01T00:00:00Z"),
+        (0.5, "2001-01-01T00:00:00.500Z"),
+        (0.0004, "2001-01-01T00:00:00Z"),
+        (-26_481_600.0, "2000-02-29T12:00:00Z"),
+        (-978_307_200.0, "1970-01-01T00:00:00Z"),
+        (-978_307_201.25, "1969-12-31T23:59:58.750Z"),
+        (-31_622_400.0, "2000-01-01T00:00:00Z"),
+    ];
+    for (absolute_time, expected) in cases {
+        let date = Date { absolute_time: absolute_time.into() };
+        assert_eq!(date.to_rfc3339(), expected);
+        assert_eq!(date.to_string(), expected);
+    }
+
+    assert_eq!(Date { absolute_time: f64::INFINITY.into() }.to_string(), "inf");
+}
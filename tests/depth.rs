@@ -0,0 +1,49 @@
+
+use bplist::{Deserializer, Error, Object};
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+
+/// Returns a document of `depth` arrays, each containing the next, around an empty array.
+fn nested_arrays(depth: usize) -> Vec<u8> {
+    let mut object = Object::Array(vec![]);
+    for _ in 1 .. depth {
+        object = Object::Array(vec![object]);
+    }
+    bplist::to_bytes(&object).unwrap()
+}
+
+#[test]
+fn test_default_recursion_limit() {
+    assert!(bplist::from_slice::<Object>(&nested_arrays(128)).is_ok());
+    assert_eq!(
+        bplist::from_slice::<Object>(&nested_arrays(129)),
+        Err(Error::RecursionLimitExceeded)
+    );
+}
+
+#[test]
+fn test_deeply_nested_arrays_exceed_recursion_limit() {
+    let data = nested_arrays(1000);
+    assert_eq!(bplist::from_slice::<Object>(&data), Err(Error::RecursionLimitExceeded));
+    assert_eq!(bplist::from_slice::<IgnoredAny>(&data), Err(Error::RecursionLimitExceeded));
+}
+
+#[test]
+fn test_configured_recursion_limit() {
+    let data = nested_arrays(8);
+
+    let mut deserializer = Deserializer::from_slice(&data).with_recursion_limit(7);
+    assert_eq!(Object::deserialize(&mut deserializer), Err(Error::RecursionLimitExceeded));
+
+    let mut deserializer = Deserializer::from_slice(&data).with_recursion_limit(8);
+    assert!(Object::deserialize(&mut deserializer).is_ok());
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_maximum_depth_exceeded_is_retained() {
+    assert_eq!(
+        Error::MaximumDepthExceeded.to_string(),
+        Error::RecursionLimitExceeded.to_string()
+    );
+}
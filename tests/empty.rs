@@ -0,0 +1,49 @@
+
+use bplist::Object;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+
+fn read_fixture(name: &str) -> Vec<u8> {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests");
+    plist_path.push(name);
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn test_deserialize_empty_dictionary() {
+    // The smallest document: a header, a one-byte root object, a one-byte offset table and a trailer.
+    let data = read_fixture("empty_dictionary.plist");
+    assert_eq!(data.len(), 42);
+
+    assert_eq!(
+        bplist::from_slice::<BTreeMap<Object, Object>>(&data),
+        Ok(BTreeMap::new())
+    );
+    assert_eq!(
+        bplist::from_slice::<Object>(&data),
+        Ok(Object::Dictionary(BTreeMap::new()))
+    );
+}
+
+#[test]
+fn test_deserialize_empty_array() {
+    let data = read_fixture("empty_array.plist");
+    assert_eq!(data.len(), 42);
+
+    assert_eq!(
+        bplist::from_slice::<Vec<Object>>(&data),
+        Ok(Vec::new())
+    );
+    assert_eq!(
+        bplist::from_slice::<Object>(&data),
+        Ok(Object::Array(Vec::new()))
+    );
+}
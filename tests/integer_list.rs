@@ -1,6 +1,10 @@
 
+use bplist::Deserializer;
+use serde::Deserialize;
+
 use std::fs;
 use std::io::Read;
+use std::num::{NonZeroU8, NonZeroU64};
 use std::path::PathBuf;
 
 #[test]
@@ -40,3 +44,201 @@ fn test_deserialize_integer_list_as_object() {
         ))
     );
 }
+
+#[test]
+fn test_deserialize_integer_list_reference_size_3() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/integer_list_reference_size_3.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(
+        bplist::from_slice::<Vec<u8>>(&data),
+        Ok(vec![1,2,3,4,5])
+    );
+}
+
+#[test]
+fn test_iterate_integer_list_object() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/integer_list.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    let object = bplist::from_slice::<bplist::Object>(&data).unwrap();
+    assert_eq!(object.iter().filter_map(bplist::Object::as_i64).sum::<i64>(), 15);
+
+    let mut count = 0;
+    for element in &object {
+        assert!(element.as_i64().is_some());
+        count += 1;
+    }
+    assert_eq!(count, 5);
+
+    assert_eq!(object.into_iter().last(), Some(bplist::Object::Integer(5)));
+}
+
+#[test]
+fn test_iterate_non_array_object() {
+    let object = bplist::Object::Integer(1);
+    assert_eq!(object.iter().count(), 0);
+    assert_eq!(object.into_iter().count(), 0);
+}
+
+#[test]
+fn test_deserialize_integer_list_as_bool() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/integer_list.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(
+        bplist::from_slice::<Vec<bool>>(&data),
+        Err(bplist::Error::ExpectedBool)
+    );
+}
+
+#[test]
+fn test_deserialize_integer_list_as_non_zero() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/integer_list.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    let values = bplist::from_slice::<Vec<NonZeroU64>>(&data).unwrap();
+    assert_eq!(values.iter().map(|value| value.get()).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    assert!(bplist::from_slice::<Vec<NonZeroU8>>(&data).is_ok());
+}
+
+#[test]
+fn test_deserialize_zero_as_non_zero() {
+    let data = bplist::to_bytes(&vec![1u64, 0]).unwrap();
+    match bplist::from_slice::<Vec<NonZeroU64>>(&data) {
+        Err(bplist::Error::Message(message)) => assert!(message.contains("nonzero"), "{}", message),
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn test_deserialize_128_bit_integers() {
+    // A list of the 128-bit integers -5 and u64::MAX.
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/integer_128.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(
+        bplist::from_slice::<Vec<i128>>(&data),
+        Ok(vec![-5, u64::MAX as i128])
+    );
+
+    // Values within the range of an i64 are folded into Object::Integer.
+    let archive = bplist::Archive::from_slice(&data).unwrap();
+    assert_eq!(archive.get_at::<bplist::Object>("/0"), Ok(bplist::Object::Integer(-5)));
+    assert_eq!(archive.get_at::<u64>("/1"), Ok(u64::MAX));
+    assert_eq!(
+        bplist::from_slice::<bplist::Object>(&data),
+        Err(bplist::Error::Message(String::from("u64 value was too large")))
+    );
+}
+
+#[test]
+fn test_deserialize_8_byte_integer_as_unsigned() {
+    // A list containing the 8-byte integer with all bits set.
+    let data = vec![
+        0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+        0xA1, 0x01, 0x13, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        0xFF, 0xFF, 0xFF, 0x08, 0x0A, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x13,
+    ];
+
+    assert_eq!(bplist::from_slice::<Vec<u64>>(&data), Ok(vec![u64::MAX]));
+    assert_eq!(bplist::from_slice::<Vec<i64>>(&data), Ok(vec![-1]));
+}
+
+#[test]
+fn test_deserialize_integer_list_as_map() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/integer_list.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(
+        bplist::from_slice::<std::collections::BTreeMap<u64, u64>>(&data),
+        Err(bplist::Error::Message(String::from("expected dictionary root, found array")))
+    );
+}
+
+#[test]
+fn test_deserialize_lenient_booleans() {
+    // A list of the integers 1 and 0 followed by the booleans true and false.
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/boolean_numbers.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(bplist::from_slice::<Vec<bool>>(&data), Err(bplist::Error::ExpectedBool));
+    assert!(bplist::from_slice::<Vec<u8>>(&data).is_err());
+
+    let mut deserializer = Deserializer::from_slice(&data).with_lenient_booleans(true);
+    assert_eq!(Vec::<bool>::deserialize(&mut deserializer), Ok(vec![true, false, true, false]));
+
+    let mut deserializer = Deserializer::from_slice(&data).with_lenient_booleans(true);
+    assert_eq!(Vec::<u8>::deserialize(&mut deserializer), Ok(vec![1, 0, 1, 0]));
+
+    // Integers other than 0 and 1 are not booleans.
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/integer_list.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    let mut deserializer = Deserializer::from_slice(&data).with_lenient_booleans(true);
+    assert_eq!(Vec::<bool>::deserialize(&mut deserializer), Err(bplist::Error::ExpectedBool));
+}
+
+#[test]
+fn test_deserialize_negative_integer_as_u64() {
+    // A u64 target receives the raw bits of a negative 8-byte integer in a document.
+    let bytes = bplist::to_bytes(&vec![-5i64]).unwrap();
+    assert_eq!(bplist::from_slice::<Vec<u64>>(&bytes), Ok(vec![18446744073709551611]));
+    assert!(bplist::from_slice::<Vec<u32>>(&bytes).is_err());
+
+    // A negative integer in the object model is rejected.
+    let object = bplist::from_slice::<bplist::Object>(&bytes).unwrap();
+    assert_eq!(
+        bplist::from_object::<Vec<u64>>(&object),
+        Err(bplist::Error::Message(String::from("invalid value: integer `-5`, expected u64")))
+    );
+    assert!(bplist::from_object::<Vec<u32>>(&object).is_err());
+}
+
+#[test]
+fn test_roundtrip_u64_greater_than_i64_max() {
+    // Values greater than i64::MAX are written as 16-byte integers with a zero upper half.
+    let bytes = bplist::to_bytes(&vec![u64::MAX]).unwrap();
+    assert_eq!(bytes[10], 0x14);
+    assert_eq!(bytes[11 .. 19], [0x00; 8]);
+    assert_eq!(bytes[19 .. 27], [0xFF; 8]);
+    assert_eq!(bplist::from_slice::<Vec<u64>>(&bytes), Ok(vec![u64::MAX]));
+    assert_eq!(bplist::from_slice::<Vec<i128>>(&bytes), Ok(vec![i128::from(u64::MAX)]));
+    assert!(bplist::from_slice::<Vec<i64>>(&bytes).is_err());
+}
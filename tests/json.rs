@@ -0,0 +1,69 @@
+#![cfg(feature = "json")]
+
+use bplist::object::{Date, Uid};
+use bplist::Object;
+use serde_json::{json, Value};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn test_point_to_json() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests");
+    plist_path.push("point.plist");
+    let object: Object = bplist::from_slice(&fs::read(plist_path).unwrap()).unwrap();
+
+    assert_eq!(Value::from(object), json!({ "x": 1, "y": 20 }));
+}
+
+#[test]
+fn test_scalars_to_json() {
+    let object = Object::Array(vec![
+        Object::Null,
+        Object::Boolean(true),
+        Object::Integer(-3),
+        Object::Real(2.5.into()),
+        Object::Real(f64::NAN.into()),
+        Object::Data(b"foobar".to_vec()),
+        Object::Date(Date { absolute_time: 599_529_600.5.into() }),
+        Object::Uid(Uid::from_u64(7)),
+        Object::String(String::from("text")),
+    ]);
+
+    let expected = json!([
+        null,
+        true,
+        -3,
+        2.5,
+        null,
+        "Zm9vYmFy",
+        "2020-01-01T00:00:00.500Z",
+        { "CF$UID": 7 },
+        "text",
+    ]);
+    assert_eq!(Value::from(object), expected);
+}
+
+#[test]
+fn test_dictionary_keys_to_json() {
+    let object = Object::Dictionary({
+        let mut map = BTreeMap::new();
+        map.insert(Object::String(String::from("name")), Object::Integer(1));
+        map.insert(Object::Integer(5), Object::Integer(2));
+        map.insert(Object::Real(5.0.into()), Object::Integer(3));
+        map.insert(Object::Boolean(false), Object::Integer(4));
+        map.insert(Object::Data(vec![0xff]), Object::Integer(6));
+        map
+    });
+
+    let expected = json!({
+        "name": 1,
+        "integer:5": 2,
+        "real:5.0": 3,
+        "boolean:false": 4,
+        "data:\"/w==\"": 6,
+    });
+    assert_eq!(Value::from(object), expected);
+}
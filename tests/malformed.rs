@@ -0,0 +1,193 @@
+
+use bplist::{Error, Object};
+
+/// Returns the `point.plist` document with the trailer's offset table offset replaced.
+fn point_with_offset_table_offset(offset_table_offset: u64) -> Vec<u8> {
+    let mut data = vec![
+        0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+        0xD2, 0x01, 0x02, 0x03, 0x04, 0x51, 0x78, 0x51,
+        0x79, 0x10, 0x01, 0x10, 0x14, 0x08, 0x0D, 0x0F,
+        0x11, 0x13, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x15,
+    ];
+    let trailer_offset = data.len() - 8;
+    data[trailer_offset ..].copy_from_slice(&offset_table_offset.to_be_bytes());
+    data
+}
+
+#[test]
+fn test_offset_table_offset_beyond_input() {
+    // A multi-gigabyte offset in a tiny document.
+    let data = point_with_offset_table_offset(0x0000_0001_0000_0000);
+    assert_eq!(
+        bplist::from_slice::<Object>(&data),
+        Err(Error::MissingOrInvalidOffsetTable)
+    );
+    assert_eq!(bplist::validate(&data), Err(Error::MissingOrInvalidOffsetTable));
+
+    let data = point_with_offset_table_offset(0x15);
+    assert_eq!(bplist::validate(&data), Ok(()));
+}
+
+#[test]
+fn test_offset_table_offset_near_maximum() {
+    // The end of the offset table cannot be represented.
+    let data = point_with_offset_table_offset(u64::MAX - 1);
+    assert_eq!(
+        bplist::from_slice::<Object>(&data),
+        Err(Error::MissingOrInvalidOffsetTable)
+    );
+}
+
+#[test]
+fn test_offset_table_length_overflows() {
+    // The number of objects multiplied by the offset table entry size cannot be represented.
+    let mut data = point_with_offset_table_offset(0x15);
+    let trailer_offset = data.len() - 32;
+    data[trailer_offset + 6] = 2;
+    data[trailer_offset + 8 .. trailer_offset + 16].copy_from_slice(&u64::MAX.to_be_bytes());
+    assert_eq!(
+        bplist::from_slice::<Object>(&data),
+        Err(Error::MissingOrInvalidOffsetTable)
+    );
+    assert_eq!(bplist::inspect(&data), Err(Error::MissingOrInvalidOffsetTable));
+}
+
+#[test]
+fn test_array_length_exceeds_input() {
+    // A root array claiming one billion elements in a 51-byte document.
+    let data = vec![
+        0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+        0xAF, 0x13, 0x00, 0x00, 0x00, 0x00, 0x3B, 0x9A,
+        0xCA, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x12,
+    ];
+    assert_eq!(
+        bplist::from_slice::<Object>(&data),
+        Err(Error::ExpectedArray)
+    );
+}
+
+#[test]
+fn test_dictionary_value_references_truncated() {
+    // A root dictionary declaring two entries with only three references before the offset table.
+    let data = vec![
+        0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+        0x51, 0x78, 0x51, 0x79, 0x10, 0x01, 0xD2, 0x00,
+        0x01, 0x02, 0x08, 0x0A, 0x0C, 0x0E, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x12,
+    ];
+    assert_eq!(
+        bplist::from_slice::<Object>(&data),
+        Err(Error::TruncatedDictionary)
+    );
+}
+
+#[test]
+fn test_half_precision_real_unsupported() {
+    // A root array containing a real with a 2-byte width, which is not a format of bplist00.
+    let data = vec![
+        0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+        0xA1, 0x01, 0x21, 0x3C, 0x00, 0x08, 0x0A, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D,
+    ];
+    let expected_error = Error::At {
+        offset: 0x0A,
+        source: Box::new(Error::InvalidOrUnsupportedObjectFormat),
+    };
+    assert_eq!(bplist::from_slice::<Object>(&data), Err(expected_error.clone()));
+    assert_eq!(bplist::from_slice::<Vec<f64>>(&data), Err(expected_error.clone()));
+    assert_eq!(bplist::validate(&data), Err(expected_error));
+}
+
+#[test]
+fn test_offset_to_object_outside_object_table() {
+    // The offset table entry for the key `x` points into the offset table itself.
+    let mut data = point_with_offset_table_offset(0x15);
+    data[0x16] = 0x17;
+
+    let error = bplist::from_slice::<Object>(&data).unwrap_err();
+    assert_eq!(error, Error::At {
+        offset: 0x17,
+        source: Box::new(Error::InvalidOffsetToObject),
+    });
+    assert_eq!(error.to_string(), "invalid offset to element in offset table at offset 0x17");
+    assert_eq!(
+        std::error::Error::source(&error).map(|source| source.to_string()),
+        Some(Error::InvalidOffsetToObject.to_string())
+    );
+}
+
+#[test]
+fn test_empty_object_table() {
+    // The offset table immediately follows the header, but the trailer declares one object.
+    let data = vec![
+        0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+        0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x08,
+    ];
+    assert_eq!(
+        bplist::from_slice::<Object>(&data),
+        Err(Error::MissingOrInvalidObjectTable)
+    );
+    assert!(bplist::inspect(&data).is_ok());
+}
+
+#[test]
+fn test_to_object_matches_from_slice() {
+    let data = point_with_offset_table_offset(0x15);
+    assert_eq!(bplist::to_object(&data), bplist::from_slice::<Object>(&data));
+    assert!(bplist::to_object(&data).is_ok());
+
+    let data = point_with_offset_table_offset(0x0000_0001_0000_0000);
+    assert_eq!(bplist::to_object(&data), Err(Error::MissingOrInvalidOffsetTable));
+}
+
+#[test]
+fn test_utf16_string_odd_length_payload() {
+    // A root array containing a UTF-16 string of one code unit with a single byte of payload.
+    let data = vec![
+        0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+        0xA1, 0x01, 0x61, 0x00, 0x08, 0x0A, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x0C,
+    ];
+    assert_eq!(
+        bplist::from_slice::<Vec<String>>(&data),
+        Err(Error::ExpectedUtf16String)
+    );
+}
+
+#[test]
+fn test_utf16_string_unpaired_surrogate() {
+    // A root array containing a UTF-16 string consisting of an unpaired high surrogate.
+    let data = vec![
+        0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+        0xA1, 0x01, 0x61, 0xD8, 0x00, 0x08, 0x0A, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D,
+    ];
+    assert_eq!(
+        bplist::from_slice::<Object>(&data),
+        Err(Error::ExpectedUtf16String)
+    );
+}
@@ -0,0 +1,480 @@
+
+use bplist::Object;
+use bplist::object::{Date, Uid};
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+
+fn read_fixture(name: &str) -> Vec<u8> {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests");
+    plist_path.push(name);
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+    data
+}
+
+/// Returns `{ "points": [1, 20], "name": "origin" }`, which consists of 7 objects.
+fn nested_fixture() -> Object {
+    Object::Dictionary({
+        let mut map = BTreeMap::new();
+        map.insert(
+            Object::String(String::from("points")),
+            Object::Array(vec![Object::Integer(1), Object::Integer(20)])
+        );
+        map.insert(
+            Object::String(String::from("name")),
+            Object::String(String::from("origin"))
+        );
+        map
+    })
+}
+
+#[test]
+fn test_clone_bounded_within_limit() {
+    let object = nested_fixture();
+    assert_eq!(object.clone_bounded(7), Ok(object.clone()));
+    assert_eq!(object.clone_bounded(100), Ok(object));
+}
+
+#[test]
+fn test_clone_bounded_exceeds_limit() {
+    let object = nested_fixture();
+    assert_eq!(
+        object.clone_bounded(6),
+        Err(bplist::Error::Message(String::from("object tree exceeds the limit of 6 nodes")))
+    );
+    assert!(object.clone_bounded(0).is_err());
+}
+
+#[test]
+fn test_approx_eq_single_and_double_precision() {
+    // An array of Float32(0.1) and Float64(0.1).
+    let data = &[
+        0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+        0xA2, 0x01, 0x02, 0x22, 0x3D, 0xCC, 0xCC, 0xCD,
+        0x23, 0x3F, 0xB9, 0x99, 0x99, 0x99, 0x99, 0x99,
+        0x9A, 0x08, 0x0B, 0x10, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x19,
+    ];
+    let reals = match bplist::from_slice::<Object>(data) {
+        Ok(Object::Array(reals)) => reals,
+        other => panic!("unexpected result: {:?}", other),
+    };
+
+    assert_ne!(reals[0], reals[1]);
+    assert!(reals[0].approx_eq(&reals[1], 1e-6));
+    assert!(!reals[0].approx_eq(&reals[1], 1e-12));
+}
+
+#[test]
+fn test_approx_eq_recurses_through_collections() {
+    let lhs = Object::Dictionary({
+        let mut map = BTreeMap::new();
+        map.insert(
+            Object::String(String::from("values")),
+            Object::Array(vec![Object::Real(1.0.into()), Object::Integer(2)])
+        );
+        map
+    });
+    let rhs = Object::Dictionary({
+        let mut map = BTreeMap::new();
+        map.insert(
+            Object::String(String::from("values")),
+            Object::Array(vec![Object::Real(1.0005.into()), Object::Integer(2)])
+        );
+        map
+    });
+
+    assert!(lhs.approx_eq(&rhs, 1e-3));
+    assert!(!lhs.approx_eq(&rhs, 1e-4));
+    assert!(!lhs.approx_eq(&nested_fixture(), 1e-3));
+}
+
+#[test]
+fn test_map_strings_uppercase() {
+    let mut object = nested_fixture();
+    object.map_strings(|value| value.to_uppercase());
+
+    let expected = Object::Dictionary({
+        let mut map = BTreeMap::new();
+        map.insert(
+            Object::String(String::from("POINTS")),
+            Object::Array(vec![Object::Integer(1), Object::Integer(20)])
+        );
+        map.insert(
+            Object::String(String::from("NAME")),
+            Object::String(String::from("ORIGIN"))
+        );
+        map
+    });
+    assert_eq!(object, expected);
+}
+
+#[test]
+fn test_map_strings_visits_every_string() {
+    let mut object = Object::Array(vec![
+        Object::String(String::from("a")),
+        nested_fixture(),
+        Object::Integer(3),
+    ]);
+    let mut visited = Vec::new();
+    object.map_strings(|value| {
+        visited.push(String::from(value));
+        String::from(value)
+    });
+
+    visited.sort();
+    assert_eq!(visited, vec!["a", "name", "origin", "points"]);
+}
+
+#[test]
+fn test_visit_mut_converts_integral_reals() {
+    let mut object = Object::Array(vec![
+        Object::Real(1.0.into()),
+        Object::Real(2.5.into()),
+        Object::Dictionary({
+            let mut map = BTreeMap::new();
+            map.insert(Object::Real(3.0.into()), Object::Real((-4.0).into()));
+            map
+        }),
+    ]);
+    object.visit_mut(&mut |object: &mut Object| {
+        if let Object::Real(value) = object {
+            if value.fract() == 0.0 {
+                *object = Object::Integer(value.0 as i64);
+            }
+        }
+    });
+
+    let expected = Object::Array(vec![
+        Object::Integer(1),
+        Object::Real(2.5.into()),
+        Object::Dictionary({
+            let mut map = BTreeMap::new();
+            map.insert(Object::Integer(3), Object::Integer(-4));
+            map
+        }),
+    ]);
+    assert_eq!(object, expected);
+}
+
+#[test]
+fn test_visit_mut_visits_contents_before_collections() {
+    let mut object = Object::Array(vec![nested_fixture(), Object::Integer(3)]);
+    let mut visited = Vec::new();
+    object.visit_mut(&mut |object: &mut Object| visited.push(object.type_name()));
+
+    assert_eq!(
+        visited,
+        vec![
+            "string", "string", "string", "integer", "integer", "array",
+            "dictionary", "integer", "array",
+        ]
+    );
+}
+
+#[test]
+fn test_get_numeric_matches_integer_and_real_keys() {
+    let object = Object::Dictionary({
+        let mut map = BTreeMap::new();
+        map.insert(Object::Integer(5), Object::String(String::from("five")));
+        map.insert(Object::Real(2.5.into()), Object::String(String::from("two and a half")));
+        map.insert(Object::Real(7.0.into()), Object::String(String::from("seven")));
+        map
+    });
+
+    assert_eq!(object.get_numeric(5.0), Some(&Object::String(String::from("five"))));
+    assert_eq!(object.get_numeric(2.5), Some(&Object::String(String::from("two and a half"))));
+    assert_eq!(object.get_numeric(7.0), Some(&Object::String(String::from("seven"))));
+    assert_eq!(object.get_numeric(3.0), None);
+    assert_eq!(Object::Integer(5).get_numeric(5.0), None);
+}
+
+#[test]
+fn test_get_numeric_compares_large_integer_keys_exactly() {
+    // 2^53 + 1 is the smallest positive integer which has no exact f64 representation.
+    let object: Object = vec![
+        (Object::Integer((1 << 53) + 1), Object::from("above")),
+        (Object::Integer(i64::MAX), Object::from("maximum")),
+    ].into_iter().collect();
+
+    assert_eq!(object.get_numeric(9_007_199_254_740_992.0), None);
+    assert_eq!(object.get_numeric(9_223_372_036_854_775_807.0), None);
+    assert_eq!(object.get_numeric(f64::INFINITY), None);
+    assert_eq!(object.get_numeric(f64::NAN), None);
+
+    let object: Object = vec![(Object::Integer(1 << 53), Object::from("exact"))]
+        .into_iter()
+        .collect();
+    assert_eq!(object.get_numeric(9_007_199_254_740_992.0), Some(&Object::from("exact")));
+    assert_eq!(object.get_numeric(9_007_199_254_740_994.0), None);
+}
+
+#[test]
+fn test_sort_arrays_recursively() {
+    let mut object = Object::Array(vec![
+        Object::Array(vec![Object::Integer(3), Object::Integer(1), Object::Integer(2)]),
+        Object::Dictionary({
+            let mut map = BTreeMap::new();
+            map.insert(
+                Object::String(String::from("points")),
+                Object::Array(vec![Object::Integer(20), Object::Integer(1)])
+            );
+            map
+        }),
+        Object::String(String::from("b")),
+        Object::String(String::from("a")),
+        Object::Integer(-1),
+    ]);
+    object.sort_arrays_recursively();
+
+    let expected = Object::Array(vec![
+        Object::Integer(-1),
+        Object::String(String::from("a")),
+        Object::String(String::from("b")),
+        Object::Array(vec![Object::Integer(1), Object::Integer(2), Object::Integer(3)]),
+        Object::Dictionary({
+            let mut map = BTreeMap::new();
+            map.insert(
+                Object::String(String::from("points")),
+                Object::Array(vec![Object::Integer(1), Object::Integer(20)])
+            );
+            map
+        }),
+    ]);
+    assert_eq!(object, expected);
+}
+
+#[test]
+fn test_data_dictionary_key() {
+    let mut plist_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/data_key.plist");
+    let data = std::fs::read(plist_path).unwrap();
+
+    let object = bplist::from_slice::<Object>(&data).unwrap();
+    let map = match &object {
+        Object::Dictionary(map) => map,
+        _ => panic!("expected dictionary"),
+    };
+
+    // The data key is distinct from the string key with the same bytes.
+    assert_eq!(map.len(), 2);
+    assert_eq!(
+        map.get(&Object::Data(b"abc".to_vec())),
+        Some(&Object::String(String::from("value")))
+    );
+    assert_eq!(
+        map.get(&Object::String(String::from("abc"))),
+        Some(&Object::String(String::from("string")))
+    );
+    assert_eq!(bplist::from_object::<Object>(&object), Ok(object.clone()));
+}
+
+#[test]
+fn test_pointer_and_path_exists() {
+    let object = nested_fixture();
+
+    assert_eq!(object.pointer(""), Some(&object));
+    assert_eq!(object.pointer("/points/1"), Some(&Object::Integer(20)));
+    assert_eq!(object.pointer("/name"), Some(&Object::String(String::from("origin"))));
+
+    assert!(object.path_exists("/points"));
+    assert!(object.path_exists("/points/0"));
+    assert!(!object.path_exists("/points/2"));
+    assert!(!object.path_exists("/points/01"));
+    assert!(!object.path_exists("/name/0"));
+    assert!(!object.path_exists("/missing"));
+    assert!(!object.path_exists("points"));
+}
+
+#[test]
+fn test_accessors() {
+    let object = nested_fixture();
+    let map = object.as_dictionary().unwrap();
+    assert_eq!(map.len(), 2);
+
+    let points = map[&Object::String(String::from("points"))].as_array().unwrap();
+    assert_eq!(points.iter().map(Object::as_i64).collect::<Vec<_>>(), vec![Some(1), Some(20)]);
+    assert_eq!(map[&Object::String(String::from("name"))].as_str(), Some("origin"));
+
+    assert_eq!(Object::Boolean(true).as_bool(), Some(true));
+    assert_eq!(Object::Real(2.5.into()).as_f64(), Some(2.5));
+    assert_eq!(Object::Data(vec![0xCA, 0xFE]).as_bytes(), Some(&[0xCA, 0xFE][..]));
+}
+
+#[test]
+fn test_accessors_mismatched_variant() {
+    let object = Object::Integer(1);
+    assert_eq!(object.as_bool(), None);
+    assert_eq!(object.as_f64(), None);
+    assert_eq!(object.as_str(), None);
+    assert_eq!(object.as_bytes(), None);
+    assert_eq!(object.as_array(), None);
+    assert_eq!(object.as_dictionary(), None);
+    assert_eq!(Object::Real(1.0.into()).as_i64(), None);
+}
+
+#[test]
+fn test_index_fixtures() {
+    let point = bplist::from_slice::<Object>(&read_fixture("point.plist")).unwrap();
+    assert_eq!(point["x"], Object::Integer(1));
+    assert_eq!(point["y"], Object::Integer(20));
+
+    let integer_list = bplist::from_slice::<Object>(&read_fixture("integer_list.plist")).unwrap();
+    assert_eq!(integer_list[0], Object::Integer(1));
+
+    let nested = nested_fixture();
+    assert_eq!(nested["points"][1], Object::Integer(20));
+}
+
+#[test]
+#[should_panic(expected = "no entry found for key `z`")]
+fn test_index_missing_key() {
+    let _ = &nested_fixture()["z"];
+}
+
+#[test]
+#[should_panic(expected = "index 2 out of bounds for array of length 2")]
+fn test_index_out_of_bounds() {
+    let _ = &nested_fixture()["points"][2];
+}
+
+#[test]
+#[should_panic(expected = "cannot index Object::String with index 0")]
+fn test_index_wrong_variant() {
+    let _ = &nested_fixture()["name"][0];
+}
+
+#[test]
+fn test_from_conversions() {
+    assert_eq!(Object::from(true), Object::Boolean(true));
+    assert_eq!(Object::from(-3i64), Object::Integer(-3));
+    assert_eq!(Object::from(2.5), Object::Real(2.5.into()));
+    assert_eq!(Object::from("origin"), Object::String(String::from("origin")));
+    assert_eq!(Object::from(String::from("origin")), Object::String(String::from("origin")));
+    assert_eq!(Object::from(vec![1u8, 20]), Object::Data(vec![1, 20]));
+    assert_eq!(
+        Object::from(vec![Object::from(1), Object::from(20)]),
+        Object::Array(vec![Object::Integer(1), Object::Integer(20)])
+    );
+}
+
+#[test]
+fn test_collect_dictionary() {
+    let object: Object = vec![
+        ("points".into(), vec![Object::from(1), Object::from(20)].into()),
+        ("name".into(), "origin".into()),
+    ].into_iter().collect();
+    assert_eq!(object, nested_fixture());
+}
+
+#[test]
+fn test_deserialize_dictionary_as_btree_map_and_hash_map() {
+    let data = read_fixture("point.plist");
+    let expected = [
+        (Object::from("x"), Object::from(1)),
+        (Object::from("y"), Object::from(20)),
+    ];
+
+    // Object::Dictionary is a BTreeMap, but any map keyed by Object can be decoded.
+    let btree_map = bplist::from_slice::<BTreeMap<Object, Object>>(&data).unwrap();
+    assert_eq!(btree_map, expected.iter().cloned().collect::<BTreeMap<_, _>>());
+
+    let hash_map = bplist::from_slice::<HashMap<Object, Object>>(&data).unwrap();
+    assert_eq!(hash_map, expected.iter().cloned().collect::<HashMap<_, _>>());
+}
+
+#[test]
+fn test_type_name() {
+    let cases = vec![
+        (Object::Null, "null"),
+        (Object::Boolean(false), "boolean"),
+        (Object::Integer(1), "integer"),
+        (Object::Real(1.0.into()), "real"),
+        (Object::Data(vec![]), "data"),
+        (Object::Date(Date { absolute_time: 0.0.into() }), "date"),
+        (Object::Uid(Uid(1)), "uid"),
+        (Object::String(String::new()), "string"),
+        (Object::Array(vec![]), "array"),
+        (Object::Dictionary(BTreeMap::new()), "dictionary"),
+    ];
+    for (object, name) in cases {
+        assert_eq!(object.type_name(), name);
+    }
+}
+
+#[test]
+fn test_empty_array_and_dictionary_are_distinct_keys() {
+    let empty_array = Object::Array(vec![]);
+    let empty_dictionary = Object::Dictionary(BTreeMap::new());
+    assert_ne!(empty_array, empty_dictionary);
+
+    // Variants are ordered by declaration, so arrays sort before dictionaries.
+    assert!(empty_array < empty_dictionary);
+
+    let mut map = BTreeMap::new();
+    map.insert(empty_dictionary.clone(), Object::from("dictionary"));
+    map.insert(empty_array.clone(), Object::from("array"));
+    assert_eq!(map.len(), 2);
+    assert_eq!(map[&empty_array], Object::from("array"));
+    assert_eq!(map[&empty_dictionary], Object::from("dictionary"));
+    assert_eq!(map.keys().collect::<Vec<_>>(), vec![&empty_array, &empty_dictionary]);
+}
+
+#[test]
+fn test_expand_embedded_plists() {
+    let innermost: Object = vec![("a".into(), 1.into())].into_iter().collect();
+    let middle = Object::Array(vec![
+        "x".into(),
+        bplist::to_bytes(&innermost).unwrap().into(),
+    ]);
+    let outer: Object = vec![
+        ("blob".into(), bplist::to_bytes(&middle).unwrap().into()),
+        ("other".into(), vec![0x62u8, 0x70].into()),
+    ].into_iter().collect();
+
+    let mut object = outer.clone();
+    object.expand_embedded_plists(0);
+    assert_eq!(object, outer);
+
+    // One level expands the outer blob, leaving the document embedded within it.
+    let mut object = outer.clone();
+    object.expand_embedded_plists(1);
+    assert_eq!(object["blob"], middle);
+
+    let mut object = outer.clone();
+    object.expand_embedded_plists(2);
+    assert_eq!(object["blob"][1], innermost);
+    assert_eq!(object["other"], Object::Data(vec![0x62, 0x70]));
+}
+
+#[test]
+fn test_null_decodes_as_unit() {
+    // A root array containing a null object and the integer 7.
+    let data = vec![
+        0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+        0xA2, 0x01, 0x02, 0x00, 0x10, 0x07, 0x08, 0x0B,
+        0x0C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x0E,
+    ];
+
+    let object: Object = bplist::from_slice(&data).unwrap();
+    assert_eq!(object, Object::Array(vec![Object::Null, Object::Integer(7)]));
+
+    let values: Vec<Option<u8>> = bplist::from_slice(&data).unwrap();
+    assert_eq!(values, vec![None, Some(7)]);
+
+    let values: Vec<Option<u8>> = bplist::from_object(&object).unwrap();
+    assert_eq!(values, vec![None, Some(7)]);
+}
@@ -30,6 +30,21 @@ fn test_deserialize_point() {
     );
 }
 
+#[test]
+fn test_deserialize_point_from_reader() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/point.plist");
+
+    let file = fs::File::open(plist_path).unwrap();
+    assert_eq!(
+        bplist::from_reader::<_, Point>(file),
+        Ok(Point {
+            x: 1,
+            y: 20
+        })
+    );
+}
+
 #[test]
 fn test_deserialize_point_as_object() {
     let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -49,3 +64,204 @@ fn test_deserialize_point_as_object() {
         }))
     );
 }
+
+#[test]
+fn test_point_object_get() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/point.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    let object = bplist::to_object(&data).unwrap();
+    assert_eq!(object.get("x"), Some(&bplist::Object::Integer(1)));
+    assert_eq!(object.get("y"), Some(&bplist::Object::Integer(20)));
+    assert_eq!(object.get("z"), None);
+    assert_eq!(bplist::Object::Integer(1).get("x"), None);
+}
+
+#[test]
+fn test_deserialize_point_from_object() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/point.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    let object = bplist::from_slice::<bplist::Object>(&data).unwrap();
+    assert_eq!(
+        object.deserialize_into::<Point>(),
+        Ok(Point {
+            x: 1,
+            y: 20
+        })
+    );
+}
+
+#[test]
+fn test_inspect_point() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/point.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(
+        bplist::inspect(&data),
+        Ok(bplist::Inspection {
+            header: bplist::Header {
+                version: (0x30, 0x30),
+            },
+            trailer: bplist::Trailer {
+                sort_version: 0,
+                offset_table_entry_size: 1,
+                object_reference_size: 1,
+                number_of_objects: 5,
+                root_object: 0,
+                offset_table_offset: 21,
+            },
+            offset_table: vec![8, 13, 15, 17, 19],
+        })
+    );
+}
+
+#[test]
+fn test_deserialize_point_with_array_key() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/point_array_key.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(
+        bplist::from_slice::<Point>(&data),
+        Err(bplist::Error::Message(String::from("expected string key, found array at object 2")))
+    );
+
+    // Non-string keys remain valid when the dictionary is not decoded as a structure.
+    assert!(bplist::from_slice::<bplist::Object>(&data).is_ok());
+}
+
+#[test]
+fn test_deserialize_point_as_ignored_any() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/point.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(
+        bplist::from_slice::<serde::de::IgnoredAny>(&data),
+        Ok(serde::de::IgnoredAny)
+    );
+}
+
+#[derive(Eq, PartialEq, Deserialize, Debug)]
+struct PartialPoint {
+    x: Option<u64>,
+    y: Option<u64>,
+    z: Option<u64>,
+}
+
+#[test]
+fn test_deserialize_point_optional_fields() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/point.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(
+        bplist::from_slice::<PartialPoint>(&data),
+        Ok(PartialPoint {
+            x: Some(1),
+            y: Some(20),
+            z: None,
+        })
+    );
+    assert_eq!(
+        bplist::from_slice::<Option<Point>>(&data),
+        Ok(Some(Point {
+            x: 1,
+            y: 20
+        }))
+    );
+}
+
+#[test]
+fn test_from_reader_io_error() {
+    struct FailingReader;
+
+    impl std::io::Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(std::io::ErrorKind::Other, "connection reset"))
+        }
+    }
+
+    assert_eq!(
+        bplist::from_reader::<_, Point>(FailingReader),
+        Err(bplist::Error::Io(String::from("connection reset")))
+    );
+}
+
+#[test]
+fn test_deserialize_point_as_sequence() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/point.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(
+        bplist::from_slice::<Vec<u64>>(&data),
+        Err(bplist::Error::Message(String::from("expected array root, found dictionary")))
+    );
+}
+
+#[test]
+fn test_reserialize_point_with_edit() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/point.plist");
+    let data = fs::read(plist_path).unwrap();
+    let archive = bplist::Archive::from_slice(&data).unwrap();
+
+    let edited = archive.reserialize_with_edit(|object| {
+        if let bplist::Object::Dictionary(map) = object {
+            map.insert("y".into(), 30.into());
+        }
+        Ok(())
+    });
+    let edited = edited.unwrap();
+    assert_eq!(bplist::validate(&edited), Ok(()));
+    assert_eq!(bplist::from_slice::<Point>(&edited), Ok(Point { x: 1, y: 30 }));
+    assert_eq!(edited.len(), data.len());
+
+    // A document written as CoreFoundation writes it is reproduced exactly.
+    assert_eq!(archive.reserialize_with_edit(|_| Ok(())), Ok(data.clone()));
+    assert_eq!(
+        archive.reserialize_with_edit(|_| Err(bplist::Error::Message(String::from("failed")))),
+        Err(bplist::Error::Message(String::from("failed")))
+    );
+}
+
+#[test]
+fn test_point_object_to_bytes() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/point.plist");
+    let data = fs::read(plist_path).unwrap();
+
+    let object = bplist::from_slice::<bplist::Object>(&data).unwrap();
+    let bytes = object.to_bytes().unwrap();
+    assert_eq!(bplist::from_slice::<bplist::Object>(&bytes), Ok(object));
+    assert_eq!(
+        bplist::Object::Integer(1).to_bytes(),
+        Err(bplist::Error::RootObjectNotArrayOrDictionary)
+    );
+}
@@ -0,0 +1,442 @@
+
+use bplist::{EncodeOptions, Object, ObjectOrder};
+use bplist::object::{Date, Uid};
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
+
+#[derive(Eq, PartialEq, Serialize, Deserialize, Debug)]
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+#[derive(PartialEq, Serialize, Deserialize, Debug)]
+struct Nested {
+    name: String,
+    points: Vec<Point>,
+    #[serde(rename = "a/b")]
+    flags: BTreeMap<String, bool>,
+}
+
+fn read_fixture(name: &str) -> Vec<u8> {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests");
+    plist_path.push(name);
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn test_roundtrip_point() {
+    let point = bplist::from_slice::<Point>(&read_fixture("point.plist")).unwrap();
+    let bytes = bplist::to_bytes(&point).unwrap();
+    assert_eq!(bplist::from_slice::<Point>(&bytes), Ok(point));
+}
+
+#[test]
+fn test_roundtrip_integer_list() {
+    let integers = bplist::from_slice::<Vec<u8>>(&read_fixture("integer_list.plist")).unwrap();
+    let bytes = bplist::to_bytes(&integers).unwrap();
+    assert_eq!(bplist::from_slice::<Vec<u8>>(&bytes), Ok(integers));
+}
+
+#[test]
+fn test_roundtrip_string_list() {
+    let strings = bplist::from_slice::<Vec<String>>(&read_fixture("string_list.plist")).unwrap();
+    let bytes = bplist::to_bytes(&strings).unwrap();
+    assert_eq!(bplist::from_slice::<Vec<String>>(&bytes), Ok(strings));
+}
+
+#[test]
+fn test_roundtrip_nested() {
+    let nested = bplist::from_slice::<Nested>(&read_fixture("nested.plist")).unwrap();
+    let bytes = bplist::to_bytes(&nested).unwrap();
+    assert_eq!(bplist::from_slice::<Nested>(&bytes), Ok(nested));
+}
+
+#[test]
+fn test_roundtrip_scalars() {
+    let values = (true, -5i64, 1.5f32, 2.25f64, String::from("naïve"), 'x');
+    let bytes = bplist::to_bytes(&values).unwrap();
+    assert_eq!(bplist::from_slice::<(bool, i64, f32, f64, String, char)>(&bytes), Ok(values));
+}
+
+#[test]
+fn test_roundtrip_long_collections() {
+    let values = (0 .. 300).map(|value| value * 1000).collect::<Vec<u32>>();
+    let bytes = bplist::to_bytes(&values).unwrap();
+    assert_eq!(bplist::from_slice::<Vec<u32>>(&bytes), Ok(values));
+
+    let string = "a".repeat(20);
+    let bytes = bplist::to_bytes(&vec![string.clone()]).unwrap();
+    assert_eq!(bplist::from_slice::<Vec<String>>(&bytes), Ok(vec![string]));
+}
+
+#[test]
+fn test_serialize_root_must_be_collection() {
+    assert_eq!(bplist::to_bytes(&5u8), Err(bplist::Error::RootObjectNotArrayOrDictionary));
+    assert_eq!(bplist::to_bytes("string"), Err(bplist::Error::RootObjectNotArrayOrDictionary));
+}
+
+#[test]
+fn test_to_writer_matches_to_bytes() {
+    let point = Point { x: 1, y: 20 };
+    let mut output = Vec::new();
+    bplist::to_writer(&mut output, &point).unwrap();
+    assert_eq!(output, bplist::to_bytes(&point).unwrap());
+}
+
+#[test]
+fn test_to_writer_io_error() {
+    struct FailingWriter;
+
+    impl std::io::Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(std::io::ErrorKind::Other, "disk full"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    assert_eq!(
+        bplist::to_writer(&mut FailingWriter, &Point { x: 1, y: 20 }),
+        Err(bplist::Error::Io(String::from("disk full")))
+    );
+}
+
+#[test]
+fn test_roundtrip_point_as_object() {
+    let object = bplist::from_slice::<Object>(&read_fixture("point.plist")).unwrap();
+    let bytes = bplist::to_bytes(&object).unwrap();
+    assert_eq!(bplist::from_slice::<Object>(&bytes), Ok(object));
+}
+
+#[test]
+fn test_roundtrip_fixtures_as_object() {
+    for name in &["date.plist", "uids.plist", "data_list.plist", "nested.plist", "keyed_archive.plist"] {
+        let object = bplist::from_slice::<Object>(&read_fixture(name)).unwrap();
+        let bytes = bplist::to_bytes(&object).unwrap();
+        assert_eq!(bplist::from_slice::<Object>(&bytes), Ok(object), "{}", name);
+    }
+}
+
+#[test]
+fn test_serialize_object_narrowest_integers() {
+    let object = Object::Array(vec![
+        Object::Integer(0xFF),
+        Object::Integer(0xFFFF),
+        Object::Integer(0xFFFF_FFFF),
+        Object::Integer(0x1_0000_0000),
+        Object::Integer(-1),
+        Object::Real(0.5.into()),
+        Object::Date(Date { absolute_time: 599_529_600.0.into() }),
+        Object::Uid(Uid(0x1234)),
+    ]);
+    let bytes = bplist::to_bytes(&object).unwrap();
+
+    // The encoded objects follow the header and the root array marker and references.
+    let objects = &bytes[8 + 1 + 8 ..];
+    let expected: &[u8] = &[
+        0x10, 0xFF,
+        0x11, 0xFF, 0xFF,
+        0x12, 0xFF, 0xFF, 0xFF, 0xFF,
+        0x13, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        0x13, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        0x23, 0x3F, 0xE0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x33, 0x41, 0xC1, 0xDE, 0x0C, 0x40, 0x00, 0x00, 0x00,
+        0x81, 0x12, 0x34,
+    ];
+    assert_eq!(&objects[.. expected.len()], expected);
+    assert_eq!(bplist::from_slice::<Object>(&bytes), Ok(object));
+}
+
+#[test]
+fn test_serialize_deduplicates_scalars() {
+    let mut map = BTreeMap::new();
+    map.insert("a", "same");
+    map.insert("b", "same");
+    map.insert("c", "same");
+    let bytes = bplist::to_bytes(&map).unwrap();
+
+    // The dictionary, three distinct keys and a single shared value.
+    let naive_count = 1 + 2 * map.len();
+    let number_of_objects = bplist::inspect(&bytes).unwrap().trailer.number_of_objects;
+    assert_eq!(number_of_objects, 5);
+    assert!(number_of_objects < naive_count);
+    assert_eq!(bplist::from_slice::<BTreeMap<String, String>>(&bytes).unwrap().len(), 3);
+
+    // Repeated keys across dictionaries are shared as well.
+    let points = vec![Point { x: 1, y: 2 }, Point { x: 2, y: 1 }];
+    let bytes = bplist::to_bytes(&points).unwrap();
+    assert_eq!(bplist::inspect(&bytes).unwrap().trailer.number_of_objects, 7);
+    assert_eq!(bplist::from_slice::<Vec<Point>>(&bytes), Ok(points));
+}
+
+#[test]
+fn test_serialize_shares_collections() {
+    let a = Point { x: 1, y: 2 };
+    let b = Point { x: 2, y: 1 };
+    let points = vec![a, b, Point { x: 1, y: 2 }, Point { x: 1, y: 2 }];
+
+    // By default each dictionary is written separately, sharing only scalars.
+    let bytes = bplist::to_bytes(&points).unwrap();
+    assert_eq!(bplist::inspect(&bytes).unwrap().trailer.number_of_objects, 9);
+
+    // The root array, two distinct dictionaries, two keys and two integers.
+    let object = bplist::from_slice::<Object>(&bytes).unwrap();
+    assert_eq!(object.count_distinct_subtrees(), 7);
+
+    let options = EncodeOptions::new().share_collections(true);
+    let shared_bytes = options.to_bytes(&points).unwrap();
+    assert_eq!(bplist::inspect(&shared_bytes).unwrap().trailer.number_of_objects, 7);
+    assert_eq!(bplist::from_slice::<Vec<Point>>(&shared_bytes), Ok(points));
+    assert_eq!(options.to_bytes(&object).unwrap(), shared_bytes);
+}
+
+#[test]
+fn test_serialize_offset_table_entry_size() {
+    // A tiny document uses 1-byte offset table entries.
+    let bytes = bplist::to_bytes(&Point { x: 1, y: 20 }).unwrap();
+    assert_eq!(bplist::inspect(&bytes).unwrap().trailer.offset_table_entry_size, 1);
+
+    // An object table extending past 65535 bytes requires 3-byte entries.
+    let object = Object::Array(vec![Object::Data(vec![0xAB; 70_000])]);
+    let bytes = bplist::to_bytes(&object).unwrap();
+    let inspection = bplist::inspect(&bytes).unwrap();
+    assert!(inspection.trailer.offset_table_offset > 65535);
+    assert_eq!(inspection.trailer.offset_table_entry_size, 3);
+    assert_eq!(bplist::from_slice::<Object>(&bytes), Ok(object));
+}
+
+#[test]
+fn test_serialize_object_reference_size() {
+    // The root array and 255 distinct integers are addressable with 1-byte references.
+    let values = (0 .. 255).collect::<Vec<u32>>();
+    let bytes = bplist::to_bytes(&values).unwrap();
+    let trailer = bplist::inspect(&bytes).unwrap().trailer;
+    assert_eq!(trailer.number_of_objects, 256);
+    assert_eq!(trailer.object_reference_size, 1);
+
+    // A 257th object requires 2-byte references.
+    let values = (0 .. 256).collect::<Vec<u32>>();
+    let bytes = bplist::to_bytes(&values).unwrap();
+    let trailer = bplist::inspect(&bytes).unwrap().trailer;
+    assert_eq!(trailer.number_of_objects, 257);
+    assert_eq!(trailer.object_reference_size, 2);
+    assert_eq!(bplist::from_slice::<Vec<u32>>(&bytes), Ok(values));
+}
+
+#[test]
+fn test_serialize_dictionary_order_is_deterministic() {
+    let keys = ["delta", "alpha", "charlie", "bravo", "echo", "a", "zz"];
+
+    let mut forward = std::collections::HashMap::new();
+    for (index, key) in keys.iter().enumerate() {
+        forward.insert(*key, index);
+    }
+    let mut reverse = std::collections::HashMap::new();
+    for (index, key) in keys.iter().enumerate().rev() {
+        reverse.insert(*key, index);
+    }
+
+    let bytes = bplist::to_bytes(&forward).unwrap();
+    assert_eq!(bytes, bplist::to_bytes(&reverse).unwrap());
+
+    // Equal objects serialize to byte-identical documents.
+    let object = bplist::from_slice::<Object>(&bytes).unwrap();
+    assert_eq!(bplist::to_bytes(&object).unwrap(), bplist::to_bytes(&object.clone()).unwrap());
+
+    // Keys are ordered by their encoded objects, so shorter ASCII strings come first.
+    let entries = bplist::Archive::from_slice(&bytes).unwrap()
+        .ordered_entries::<String, usize>(0)
+        .unwrap();
+    let ordered_keys = entries.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>();
+    assert_eq!(ordered_keys, vec!["a", "zz", "echo", "alpha", "bravo", "delta", "charlie"]);
+}
+
+#[test]
+fn test_serialize_dictionary_integer_keys() {
+    let mut map = BTreeMap::new();
+    map.insert(-1i64, String::from("minus one"));
+    map.insert(2, String::from("two"));
+    map.insert(300, String::from("three hundred"));
+
+    let bytes = bplist::to_bytes(&map).unwrap();
+    let object = bplist::from_slice::<Object>(&bytes).unwrap();
+
+    let mut expected = BTreeMap::new();
+    expected.insert(Object::Integer(-1), Object::String(String::from("minus one")));
+    expected.insert(Object::Integer(2), Object::String(String::from("two")));
+    expected.insert(Object::Integer(300), Object::String(String::from("three hundred")));
+    assert_eq!(object, Object::Dictionary(expected));
+
+    // Integer keys decode back into the original map.
+    assert_eq!(bplist::from_slice::<BTreeMap<i64, String>>(&bytes).unwrap(), map);
+}
+
+#[test]
+fn test_reencode_point_byte_exact() {
+    let data = read_fixture("point.plist");
+    let object = bplist::from_slice::<Object>(&data).unwrap();
+
+    // The root dictionary only references scalars, so both orders are identical.
+    for &order in &[ObjectOrder::DepthFirst, ObjectOrder::BreadthFirst] {
+        let options = EncodeOptions::new().object_order(order);
+        assert_eq!(options.to_bytes(&object).unwrap(), data, "{:?}", order);
+    }
+}
+
+#[test]
+fn test_reencode_nested_object_order() {
+    let data = read_fixture("nested.plist");
+    let object = bplist::from_slice::<Object>(&data).unwrap();
+
+    // The fixture was written depth-first, which is also the default.
+    let depth_first = EncodeOptions::new().object_order(ObjectOrder::DepthFirst);
+    assert_eq!(depth_first.to_bytes(&object).unwrap(), data);
+    assert_eq!(bplist::to_bytes(&object).unwrap(), data);
+
+    let breadth_first = EncodeOptions::new().object_order(ObjectOrder::BreadthFirst);
+    let bytes = breadth_first.to_bytes(&object).unwrap();
+    assert_ne!(bytes, data);
+    assert_eq!(bplist::from_slice::<Object>(&bytes), Ok(object));
+}
+
+#[derive(PartialEq, Serialize, Deserialize, Debug)]
+struct Labeled {
+    label: Option<String>,
+    count: Option<u64>,
+}
+
+#[test]
+fn test_roundtrip_none_as_fill() {
+    let labeled = Labeled { label: None, count: Some(3) };
+    let bytes = bplist::to_bytes(&labeled).unwrap();
+
+    // The None value is written as a fill object.
+    assert!(bytes[8 ..].contains(&0x0F));
+    assert_eq!(bplist::from_slice::<Labeled>(&bytes), Ok(labeled));
+
+    let values = vec![None, Some(1u8), None];
+    let bytes = bplist::to_bytes(&values).unwrap();
+    assert_eq!(bplist::from_slice::<Vec<Option<u8>>>(&bytes), Ok(values));
+    assert_eq!(bplist::from_slice::<Vec<()>>(&bplist::to_bytes(&vec![()]).unwrap()), Ok(vec![()]));
+}
+
+#[test]
+fn test_roundtrip_result() {
+    for name in &["result_ok.plist", "result_err.plist"] {
+        let data = read_fixture(name);
+        let result = bplist::from_slice::<Result<u64, String>>(&data).unwrap();
+        let bytes = bplist::to_bytes(&result).unwrap();
+        assert_eq!(bplist::from_slice::<Result<u64, String>>(&bytes), Ok(result));
+    }
+
+    let result: Result<u64, String> = Err(String::from("not found"));
+    let object = bplist::from_slice::<Object>(&bplist::to_bytes(&result).unwrap()).unwrap();
+    let entries = vec![(Object::from("Err"), Object::from("not found"))];
+    assert_eq!(object, entries.into_iter().collect::<Object>());
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+enum Channel {
+    Stable,
+    Beta,
+}
+
+#[derive(PartialEq, Serialize, Deserialize, Debug)]
+enum Shape {
+    Empty,
+    Circle(u64),
+    Line(u64, u64),
+    Rectangle { width: u64, height: u64 },
+}
+
+#[derive(PartialEq, Serialize, Deserialize, Debug)]
+struct Release {
+    channel: Channel,
+    shapes: Vec<Shape>,
+}
+
+#[test]
+fn test_roundtrip_enum_variants() {
+    let release = Release {
+        channel: Channel::Beta,
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(2),
+            Shape::Line(3, 4),
+            Shape::Rectangle { width: 5, height: 6 },
+        ],
+    };
+    let bytes = bplist::to_bytes(&release).unwrap();
+    assert_eq!(bplist::from_slice::<Release>(&bytes), Ok(release));
+
+    // A unit variant is written as its name.
+    let object = bplist::from_slice::<Object>(&bytes).unwrap();
+    assert_eq!(object.get("channel"), Some(&Object::from("Beta")));
+}
+
+#[derive(PartialEq, Serialize, Deserialize, Debug)]
+struct Host {
+    address: IpAddr,
+}
+
+#[test]
+fn test_roundtrip_ip_address() {
+    let host = Host { address: IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)) };
+    let bytes = bplist::to_bytes(&host).unwrap();
+    assert_eq!(bplist::from_slice::<Host>(&bytes), Ok(host));
+}
+
+#[derive(PartialEq, Serialize, Deserialize, Debug)]
+struct Measurement<T> {
+    value: T,
+}
+
+#[test]
+fn test_deserialize_f32_requires_single_precision() {
+    let single = bplist::to_bytes(&Measurement { value: 1.5f32 }).unwrap();
+    assert_eq!(bplist::from_slice::<Measurement<f32>>(&single), Ok(Measurement { value: 1.5 }));
+
+    let double = bplist::to_bytes(&Measurement { value: 1.5f64 }).unwrap();
+    assert_eq!(
+        bplist::from_slice::<Measurement<f32>>(&double),
+        Err(bplist::Error::ExpectedFloat32)
+    );
+    assert_eq!(bplist::from_slice::<Measurement<f64>>(&double), Ok(Measurement { value: 1.5 }));
+}
+
+#[test]
+fn test_canonicalize_bytes() {
+    let point = read_fixture("point.plist");
+
+    // The same dictionary with its keys reversed, wide integers and 2-byte offsets.
+    let wide = vec![
+        0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+        0xD2, 0x01, 0x02, 0x03, 0x04, 0x51, 0x79, 0x51,
+        0x78, 0x13, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x14, 0x11, 0x00, 0x01, 0x00, 0x08, 0x00,
+        0x0D, 0x00, 0x0F, 0x00, 0x11, 0x00, 0x1A, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x01, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1D,
+    ];
+    assert_eq!(bplist::from_slice::<Point>(&wide), Ok(Point { x: 1, y: 20 }));
+
+    let canonical = bplist::canonicalize_bytes(&point).unwrap();
+    assert_eq!(bplist::canonicalize_bytes(&wide).unwrap(), canonical);
+    assert_eq!(canonical, point);
+    assert_eq!(bplist::canonicalize_bytes(&canonical).unwrap(), canonical);
+}
@@ -0,0 +1,130 @@
+
+use serde::Deserialize;
+
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+
+#[test]
+fn test_deserialize_string_list_borrowed() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/string_list.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    let strings = bplist::from_slice::<Vec<&str>>(&data).unwrap();
+    assert_eq!(strings, vec!["alpha", "beta", "gamma"]);
+
+    // Each element is borrowed directly from the input.
+    let input_range = data.as_ptr_range();
+    for string in strings {
+        assert!(input_range.contains(&string.as_ptr()));
+    }
+}
+
+#[test]
+fn test_deserialize_data_list_borrowed() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/data_list.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    let buffers = bplist::from_slice::<Vec<&[u8]>>(&data).unwrap();
+    assert_eq!(buffers, vec![&[0x01, 0x02, 0x03][..], &[0xFF][..]]);
+
+    // Each element is borrowed directly from the input.
+    let input_range = data.as_ptr_range();
+    for buffer in buffers {
+        assert!(input_range.contains(&buffer.as_ptr()));
+    }
+}
+
+#[test]
+fn test_deserialize_latin1_string_strict() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/latin1_string.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    assert_eq!(
+        bplist::from_slice::<Vec<String>>(&data),
+        Err(bplist::Error::ExpectedAsciiString)
+    );
+}
+
+#[test]
+fn test_deserialize_latin1_string_lenient() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/latin1_string.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    let mut deserializer = bplist::Deserializer::from_slice(&data).with_lenient_ascii(true);
+    assert_eq!(
+        Vec::<String>::deserialize(&mut deserializer),
+        Ok(vec![String::from("café")])
+    );
+}
+
+#[test]
+fn test_deserialize_empty_strings() {
+    // A root array of an empty ASCII string and an empty UTF-16 string.
+    let data = vec![
+        0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+        0xA2, 0x01, 0x02, 0x50, 0x60, 0x08, 0x0B, 0x0C,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0D,
+    ];
+
+    assert_eq!(
+        bplist::from_slice::<Vec<String>>(&data),
+        Ok(vec![String::new(), String::new()])
+    );
+    assert_eq!(
+        bplist::from_slice::<bplist::Object>(&data),
+        Ok(bplist::Object::Array(vec!["".into(), "".into()]))
+    );
+
+    // Only the ASCII string can be borrowed from the input.
+    assert_eq!(
+        bplist::from_slice::<(&str, String)>(&data),
+        Ok(("", String::new()))
+    );
+}
+
+#[test]
+fn test_deserialize_ip_address_from_string() {
+    #[derive(serde::Serialize)]
+    struct StoredHost {
+        ip: String,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Host {
+        ip: std::net::IpAddr,
+    }
+
+    let data = bplist::to_bytes(&StoredHost { ip: String::from("127.0.0.1") }).unwrap();
+    assert_eq!(
+        bplist::from_slice::<Host>(&data),
+        Ok(Host { ip: std::net::Ipv4Addr::LOCALHOST.into() })
+    );
+    assert_eq!(
+        bplist::from_object::<Host>(&bplist::to_object(&data).unwrap()),
+        Ok(Host { ip: std::net::Ipv4Addr::LOCALHOST.into() })
+    );
+
+    // When not human readable, an address is expected in its compact enum form.
+    let mut deserializer = bplist::Deserializer::from_slice(&data).with_human_readable(false);
+    assert!(Host::deserialize(&mut deserializer).is_err());
+}
@@ -0,0 +1,130 @@
+
+use serde::Deserialize;
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+
+#[derive(PartialEq, Deserialize, Debug)]
+#[serde(tag = "type")]
+enum Shape {
+    Circle { radius: u64 },
+    Rectangle { width: u64, height: u64 },
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Deserialize, Debug)]
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+#[derive(PartialEq, Deserialize, Debug)]
+enum Variant {
+    Unit,
+    Newtype(u64),
+    Tuple(u64, u64),
+    Struct { x: u64 },
+}
+
+fn read_fixture(name: &str) -> Vec<u8> {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests");
+    plist_path.push(name);
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn test_deserialize_internally_tagged_enum() {
+    let data = read_fixture("tagged_shapes.plist");
+    assert_eq!(
+        bplist::from_slice::<Vec<Shape>>(&data),
+        Ok(vec![
+            Shape::Circle { radius: 2 },
+            Shape::Rectangle { width: 3, height: 4 },
+        ])
+    );
+}
+
+#[test]
+fn test_deserialize_internally_tagged_enum_from_object() {
+    let data = read_fixture("tagged_shapes.plist");
+    let object = bplist::from_slice::<bplist::Object>(&data).unwrap();
+    assert_eq!(
+        bplist::from_object::<Vec<Shape>>(&object),
+        Ok(vec![
+            Shape::Circle { radius: 2 },
+            Shape::Rectangle { width: 3, height: 4 },
+        ])
+    );
+}
+
+#[test]
+fn test_deserialize_result() {
+    let data = read_fixture("result_ok.plist");
+    assert_eq!(bplist::from_slice::<Result<u64, String>>(&data), Ok(Ok(5)));
+
+    let data = read_fixture("result_err.plist");
+    assert_eq!(
+        bplist::from_slice::<Result<u64, String>>(&data),
+        Ok(Err(String::from("not found")))
+    );
+}
+
+#[test]
+fn test_deserialize_externally_tagged_enum() {
+    let data = read_fixture("enum_variants.plist");
+    assert_eq!(
+        bplist::from_slice::<Vec<Variant>>(&data),
+        Ok(vec![
+            Variant::Unit,
+            Variant::Newtype(1),
+            Variant::Tuple(2, 3),
+            Variant::Struct { x: 4 },
+        ])
+    );
+}
+
+#[test]
+fn test_deserialize_enum_from_dictionary_with_several_entries() {
+    let data = read_fixture("point.plist");
+    assert_eq!(
+        bplist::from_slice::<Result<u64, u64>>(&data),
+        Err(bplist::Error::Message(String::from(
+            "invalid length 2, expected a dictionary with a single entry"
+        )))
+    );
+}
+
+#[test]
+fn test_deserialize_map_with_enum_keys() {
+    let data = read_fixture("color_counts.plist");
+    let expected = vec![(Color::Red, 1), (Color::Green, 2), (Color::Blue, 3)]
+        .into_iter()
+        .collect::<HashMap<Color, u64>>();
+
+    assert_eq!(bplist::from_slice::<HashMap<Color, u64>>(&data), Ok(expected.clone()));
+
+    let object = bplist::from_slice::<bplist::Object>(&data).unwrap();
+    assert_eq!(bplist::from_object::<HashMap<Color, u64>>(&object), Ok(expected));
+}
+
+#[test]
+fn test_deserialize_externally_tagged_enum_from_object() {
+    let data = read_fixture("enum_variants.plist");
+    let object = bplist::from_slice::<bplist::Object>(&data).unwrap();
+    assert_eq!(
+        bplist::from_object::<Vec<Variant>>(&object),
+        Ok(vec![
+            Variant::Unit,
+            Variant::Newtype(1),
+            Variant::Tuple(2, 3),
+            Variant::Struct { x: 4 },
+        ])
+    );
+}
@@ -0,0 +1,123 @@
+
+use bplist::Object;
+use bplist::object::Uid;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+
+#[test]
+fn test_deserialize_uids_as_object() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/uids.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    let object = bplist::from_slice::<Object>(&data).unwrap();
+    let top = match object {
+        Object::Dictionary(mut map) => map.remove(&Object::String(String::from("$top"))),
+        _ => None,
+    };
+    assert_eq!(
+        top,
+        Some(Object::Dictionary({
+            let mut map = BTreeMap::new();
+            map.insert(Object::String(String::from("root")), Object::Uid(Uid(0)));
+            map
+        }))
+    );
+}
+
+#[test]
+fn test_deserialize_uid_field_name_as_dictionary_key() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/uid_field_name.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+
+    // A user key equal to the name of the private UID field is not a UID.
+    assert_eq!(
+        bplist::from_slice::<Object>(&data),
+        Ok(Object::Dictionary({
+            let mut map = BTreeMap::new();
+            map.insert(
+                Object::String(String::from("__bplist_private_CF$UID_value")),
+                Object::Integer(7)
+            );
+            map
+        }))
+    );
+}
+
+#[test]
+fn test_serialize_uid_roundtrip() {
+    let uids = vec![Uid(0x12), Uid(0x1234_5678)];
+    let bytes = bplist::to_bytes(&uids).unwrap();
+
+    // The UID length nibble encodes one less than the number of bytes which follow.
+    assert!(bytes.windows(2).any(|window| window == [0b1000_0000, 0x12]));
+    assert!(bytes.windows(5).any(|window| window == [0b1000_0011, 0x12, 0x34, 0x56, 0x78]));
+    assert_eq!(bplist::from_slice::<Vec<Uid>>(&bytes), Ok(uids));
+}
+
+#[test]
+fn test_uid_from_u64_minimal_byte_len() {
+    let cases = [(0, 1), (255, 1), (256, 2), (0x1_0000, 4), (0x1_0000_0000, 8), (u64::MAX, 8)];
+    for &(value, byte_len) in &cases {
+        let uid = Uid::from_u64(value);
+        assert_eq!(uid.as_u64(), value);
+        assert_eq!(uid.byte_len(), byte_len, "{}", value);
+
+        // The encoded object is the marker byte followed by the minimal encoding.
+        let bytes = bplist::to_bytes(&vec![uid.clone()]).unwrap();
+        let marker = 0b1000_0000 | (byte_len as u8 - 1);
+        let mut object = vec![marker];
+        object.extend_from_slice(&value.to_be_bytes()[8 - byte_len ..]);
+        assert!(bytes.windows(object.len()).any(|window| window == &object[..]), "{}", value);
+        assert_eq!(bplist::from_slice::<Vec<Uid>>(&bytes), Ok(vec![uid]));
+    }
+}
+
+#[test]
+fn test_deserialize_1_and_4_byte_uids() {
+    // A root array of the 1-byte UID 5 and the 4-byte UID 0x10000.
+    let data = vec![
+        0x62, 0x70, 0x6C, 0x69, 0x73, 0x74, 0x30, 0x30,
+        0xA2, 0x01, 0x02, 0x80, 0x05, 0x83, 0x00, 0x01,
+        0x00, 0x00, 0x08, 0x0B, 0x0D, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x12,
+    ];
+
+    let uids = bplist::from_slice::<Vec<Uid>>(&data).unwrap();
+    assert_eq!(uids.iter().map(Uid::as_u64).collect::<Vec<u64>>(), vec![5, 0x1_0000]);
+    assert_eq!(uids.iter().map(Uid::byte_len).collect::<Vec<usize>>(), vec![1, 4]);
+    assert_eq!(uids, vec![Uid::from_u64(5), Uid::from_u64(0x1_0000)]);
+
+    // Re-encoding produces the same minimal widths.
+    assert_eq!(bplist::to_bytes(&uids).unwrap(), data);
+}
+
+#[test]
+fn test_uid_display_and_lower_hex() {
+    let cases = [
+        (0, "00"),
+        (0x0a, "0a"),
+        (0x100, "0100"),
+        (0x0a0b0c, "0a0b0c"),
+        (u64::MAX, "ffffffffffffffff"),
+    ];
+    for &(value, hex) in &cases {
+        let uid = Uid::from_u64(value);
+        assert_eq!(format!("{:x}", uid), hex);
+        assert_eq!(format!("{:#x}", uid), format!("0x{}", hex));
+        assert_eq!(uid.to_string(), format!("Uid(0x{})", hex));
+    }
+}
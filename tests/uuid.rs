@@ -0,0 +1,44 @@
+#![cfg(feature = "uuid")]
+
+use bplist::{Deserializer, Object};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+
+fn read_uuid_list() -> Vec<u8> {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests/uuid_list.plist");
+
+    let mut file = fs::File::open(plist_path).unwrap();
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn test_deserialize_uuid_from_data() {
+    let data = read_uuid_list();
+
+    // Uuid requests its compact byte representation when the deserializer is not
+    // human readable. The 16 bytes of the data object are the UUID in big-endian order.
+    let mut deserializer = Deserializer::from_slice(&data).with_human_readable(false);
+    let uuids = Vec::<Uuid>::deserialize(&mut deserializer).unwrap();
+    assert_eq!(uuids, vec![Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap()]);
+    assert_eq!(uuids[0].as_bytes()[0 .. 4], [0x67, 0xE5, 0x50, 0x44]);
+}
+
+#[test]
+fn test_uuid_object_conversions() {
+    let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+    let object = Object::from(uuid);
+    assert_eq!(object.as_bytes().map(<[u8]>::len), Some(16));
+    assert_eq!(object.as_uuid(), Some(uuid));
+
+    let list = bplist::from_slice::<Object>(&read_uuid_list()).unwrap();
+    assert_eq!(list[0].as_uuid(), Some(uuid));
+    assert_eq!(Object::Data(vec![0; 15]).as_uuid(), None);
+    assert_eq!(Object::from("67e55044-10b1-426f-9247-bb680e5fe0c8").as_uuid(), None);
+}
@@ -0,0 +1,85 @@
+
+use bplist::object::{Date, Uid};
+use bplist::Object;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn test_point_to_xml() {
+    let mut plist_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    plist_path.push("tests");
+    plist_path.push("point.plist");
+    let object: Object = bplist::from_slice(&fs::read(plist_path).unwrap()).unwrap();
+
+    // As written by `plutil -convert xml1`.
+    let expected = concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" ",
+        "\"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+        "<plist version=\"1.0\">\n",
+        "<dict>\n",
+        "\t<key>x</key>\n",
+        "\t<integer>1</integer>\n",
+        "\t<key>y</key>\n",
+        "\t<integer>20</integer>\n",
+        "</dict>\n",
+        "</plist>\n",
+    );
+    assert_eq!(bplist::to_xml(&object), expected);
+}
+
+#[test]
+fn test_objects_to_xml() {
+    let object = Object::Dictionary({
+        let mut map = BTreeMap::new();
+        map.insert(
+            Object::String(String::from("a&b")),
+            Object::Array(vec![
+                Object::Data(b"foo".to_vec()),
+                Object::Date(Date { absolute_time: 599_529_600.75.into() }),
+                Object::Real(2.5.into()),
+                Object::Real(5.0.into()),
+                Object::Real(f64::NEG_INFINITY.into()),
+                Object::String(String::from("<x>")),
+                Object::Boolean(false),
+                Object::Null,
+                Object::Array(Vec::new()),
+                Object::Dictionary(BTreeMap::new()),
+            ])
+        );
+        map.insert(Object::String(String::from("e")), Object::Uid(Uid::from_u64(7)));
+        map.insert(Object::Integer(-3), Object::Integer(i64::MIN));
+        map
+    });
+
+    let expected = concat!(
+        "<dict>\n",
+        "\t<key>integer:-3</key>\n",
+        "\t<integer>-9223372036854775808</integer>\n",
+        "\t<key>a&amp;b</key>\n",
+        "\t<array>\n",
+        "\t\t<data>Zm9v</data>\n",
+        "\t\t<date>2020-01-01T00:00:00Z</date>\n",
+        "\t\t<real>2.5</real>\n",
+        "\t\t<real>5</real>\n",
+        "\t\t<real>-infinity</real>\n",
+        "\t\t<string>&lt;x&gt;</string>\n",
+        "\t\t<false/>\n",
+        "\t\t<string></string>\n",
+        "\t\t<array/>\n",
+        "\t\t<dict/>\n",
+        "\t</array>\n",
+        "\t<key>e</key>\n",
+        "\t<dict>\n",
+        "\t\t<key>CF$UID</key>\n",
+        "\t\t<integer>7</integer>\n",
+        "\t</dict>\n",
+        "</dict>\n",
+        "</plist>\n",
+    );
+    let xml = bplist::to_xml(&object);
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+    assert!(xml.ends_with(expected), "{}", xml);
+}